@@ -0,0 +1,303 @@
+use crate::collector::{
+    list_sysfs_dir, parse_flat_keyed, read_sysfs_string, read_sysfs_value, Collector, Metric,
+    MetricSample, MetricType,
+};
+use crate::error::CollectorError;
+use std::fs;
+use std::path::Path;
+
+const SYS_FS_BTRFS_PATH: &str = "/sys/fs/btrfs";
+
+/// Block group types under `allocation/`.
+const BLOCK_GROUP_TYPES: &[&str] = &["data", "metadata", "system"];
+
+/// Space allocated to one block group type with one profile, e.g. metadata
+/// stored as raid1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtrfsAllocation {
+    pub block_group_type: String,
+    pub profile: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+}
+
+/// Error counters of one device of a filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtrfsDeviceErrors {
+    pub devid: String,
+    /// (`write`, `read`, `flush`, `corruption`, `generation`) counts,
+    /// sorted by type.
+    pub errors: Vec<(String, u64)>,
+}
+
+/// A mounted btrfs filesystem from /sys/fs/btrfs/<fsid>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtrfsStats {
+    pub fsid: String,
+    /// Empty if the filesystem has no label.
+    pub label: String,
+    pub allocations: Vec<BtrfsAllocation>,
+    /// Only on kernels with `devinfo/<devid>/error_stats` (5.14+).
+    pub device_errors: Vec<BtrfsDeviceErrors>,
+}
+
+/// Read the allocation and device error stats of the filesystem in `dir`.
+pub fn read_btrfs(dir: &Path) -> Result<BtrfsStats, CollectorError> {
+    let fsid = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut allocations = Vec::new();
+    for block_group_type in BLOCK_GROUP_TYPES {
+        let type_dir = dir.join("allocation").join(block_group_type);
+        // Profile directories such as `single`, `dup` or `raid1` sit next to
+        // the per-type summary files.
+        let profiles = match list_sysfs_dir(&type_dir, "") {
+            Ok(profiles) => profiles,
+            Err(CollectorError::NotSupported { .. }) => continue,
+            Err(e) => return Err(e),
+        };
+        for profile_dir in profiles.into_iter().filter(|p| p.is_dir()) {
+            let (Some(total_bytes), Some(used_bytes)) = (
+                read_sysfs_value(&profile_dir.join("total_bytes")),
+                read_sysfs_value(&profile_dir.join("used_bytes")),
+            ) else {
+                continue;
+            };
+            allocations.push(BtrfsAllocation {
+                block_group_type: block_group_type.to_string(),
+                profile: profile_dir
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                total_bytes,
+                used_bytes,
+            });
+        }
+    }
+
+    let mut device_errors = Vec::new();
+    if let Ok(devices) = list_sysfs_dir(&dir.join("devinfo"), "") {
+        for device_dir in devices {
+            let path = device_dir.join("error_stats");
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let mut errors: Vec<(String, u64)> =
+                parse_flat_keyed(&content, &path.display().to_string())?
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = key.strip_suffix("_errs").unwrap_or(&key).to_string();
+                        (key, value)
+                    })
+                    .collect();
+            errors.sort();
+            device_errors.push(BtrfsDeviceErrors {
+                devid: device_dir
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                errors,
+            });
+        }
+    }
+
+    Ok(BtrfsStats {
+        fsid,
+        label: read_sysfs_string(&dir.join("label")).unwrap_or_default(),
+        allocations,
+        device_errors,
+    })
+}
+
+pub struct BtrfsCollector;
+
+impl Collector for BtrfsCollector {
+    fn name(&self) -> &'static str {
+        "btrfs"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(SYS_FS_BTRFS_PATH))
+    }
+}
+
+impl BtrfsCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        // Besides one directory per filesystem, /sys/fs/btrfs has `features`
+        // and other global entries without an `allocation` directory.
+        let filesystems = list_sysfs_dir(root, "")?
+            .into_iter()
+            .filter(|p| p.join("allocation").is_dir())
+            .map(|p| read_btrfs(&p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let fsid_label = |fs: &BtrfsStats| ("fsid".to_string(), fs.fsid.clone());
+
+        let allocation_samples = |value_fn: fn(&BtrfsAllocation) -> u64| -> Vec<MetricSample> {
+            filesystems
+                .iter()
+                .flat_map(|fs| {
+                    fs.allocations.iter().map(move |a| MetricSample {
+                        labels: vec![
+                            fsid_label(fs),
+                            ("block_group_type".to_string(), a.block_group_type.clone()),
+                            ("profile".to_string(), a.profile.clone()),
+                        ],
+                        value: value_fn(a) as f64,
+                    })
+                })
+                .collect()
+        };
+
+        Ok(vec![
+            Metric {
+                name: "sysmetrics_btrfs_info".to_string(),
+                help: "Label of a btrfs filesystem.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: filesystems
+                    .iter()
+                    .map(|fs| MetricSample {
+                        labels: vec![fsid_label(fs), ("label".to_string(), fs.label.clone())],
+                        value: 1.0,
+                    })
+                    .collect(),
+            },
+            Metric {
+                name: "sysmetrics_btrfs_allocation_size_bytes".to_string(),
+                help: "Space allocated to a block group type and profile.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: allocation_samples(|a| a.total_bytes),
+            },
+            Metric {
+                name: "sysmetrics_btrfs_allocation_used_bytes".to_string(),
+                help: "Space used within the allocation of a block group type and profile."
+                    .to_string(),
+                metric_type: MetricType::Gauge,
+                samples: allocation_samples(|a| a.used_bytes),
+            },
+            Metric {
+                name: "sysmetrics_btrfs_device_errors_total".to_string(),
+                help: "Errors seen on a btrfs device, by type.".to_string(),
+                metric_type: MetricType::Counter,
+                samples: filesystems
+                    .iter()
+                    .flat_map(|fs| {
+                        fs.device_errors.iter().flat_map(move |d| {
+                            d.errors
+                                .iter()
+                                .map(move |(error_type, count)| MetricSample {
+                                    labels: vec![
+                                        fsid_label(fs),
+                                        ("devid".to_string(), d.devid.clone()),
+                                        ("type".to_string(), error_type.clone()),
+                                    ],
+                                    value: *count as f64,
+                                })
+                        })
+                    })
+                    .collect(),
+            },
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::assert_missing_root_not_supported;
+
+    const BTRFS_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/fs/btrfs");
+    const RAID1_FSID: &str = "5f3b2c1a-9d4e-4b7a-8c6d-1e2f3a4b5c6d";
+    const SINGLE_FSID: &str = "a1b2c3d4-0000-4111-8222-333344445555";
+
+    #[test]
+    fn test_read_btrfs() {
+        let stats = read_btrfs(&Path::new(BTRFS_FIXTURE_DIR).join(RAID1_FSID)).unwrap();
+        assert_eq!(stats.fsid, RAID1_FSID);
+        assert_eq!(stats.label, "backups");
+        assert_eq!(stats.allocations.len(), 3);
+        assert_eq!(
+            stats.allocations[1],
+            BtrfsAllocation {
+                block_group_type: "metadata".to_string(),
+                profile: "raid1".to_string(),
+                total_bytes: 10737418240,
+                used_bytes: 10200547328,
+            }
+        );
+        assert_eq!(
+            stats.device_errors[0],
+            BtrfsDeviceErrors {
+                devid: "1".to_string(),
+                errors: vec![
+                    ("corruption".to_string(), 1),
+                    ("flush".to_string(), 0),
+                    ("generation".to_string(), 0),
+                    ("read".to_string(), 3),
+                    ("write".to_string(), 0),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_btrfs_without_label_or_error_stats() {
+        let stats = read_btrfs(&Path::new(BTRFS_FIXTURE_DIR).join(SINGLE_FSID)).unwrap();
+        assert_eq!(stats.label, "");
+        assert!(stats.device_errors.is_empty());
+        let profiles: Vec<&str> = stats
+            .allocations
+            .iter()
+            .map(|a| a.profile.as_str())
+            .collect();
+        assert_eq!(profiles, vec!["single", "dup", "dup"]);
+    }
+
+    #[test]
+    fn test_btrfs_collector_metrics() {
+        let metrics = BtrfsCollector
+            .collect_from_dir(Path::new(BTRFS_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics.len(), 4);
+
+        // `features` is not a filesystem.
+        let info = &metrics[0];
+        assert_eq!(info.samples.len(), 2);
+        assert_eq!(
+            info.samples[0].labels,
+            vec![
+                ("fsid".to_string(), RAID1_FSID.to_string()),
+                ("label".to_string(), "backups".to_string()),
+            ]
+        );
+
+        let size = &metrics[1];
+        assert_eq!(size.samples.len(), 6);
+        assert_eq!(
+            size.samples[3].labels,
+            vec![
+                ("fsid".to_string(), SINGLE_FSID.to_string()),
+                ("block_group_type".to_string(), "data".to_string()),
+                ("profile".to_string(), "single".to_string()),
+            ]
+        );
+        assert_eq!(size.samples[3].value, 53687091200.0);
+        assert_eq!(metrics[2].samples[1].value, 10200547328.0);
+
+        let errors = &metrics[3];
+        assert_eq!(errors.metric_type, MetricType::Counter);
+        assert_eq!(errors.samples.len(), 2 * 5);
+        assert_eq!(errors.samples[3].labels[2].1, "read");
+        assert_eq!(errors.samples[3].value, 3.0);
+    }
+
+    #[test]
+    fn test_btrfs_collector_missing_root() {
+        assert_missing_root_not_supported(|root| BtrfsCollector.collect_from_dir(root));
+    }
+}
@@ -0,0 +1,468 @@
+use crate::collector::{parse_flat_keyed, Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const SYS_FS_CGROUP_PATH: &str = "/sys/fs/cgroup";
+const MICROSECONDS_PER_SECOND: f64 = 1_000_000.0;
+
+/// Per-device counters from `io.stat`. The device is identified by its
+/// `major:minor` numbers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CgroupIoStat {
+    pub device: String,
+    pub rbytes: u64,
+    pub wbytes: u64,
+    pub rios: u64,
+    pub wios: u64,
+}
+
+/// Parse `io.stat` content (`8:0 rbytes=1 wbytes=2 rios=3 wios=4 ...`).
+/// Keys other than bytes and operations (discards, cost model stats) are
+/// ignored.
+pub fn parse_io_stat(content: &str, path: &str) -> Result<Vec<CgroupIoStat>, CollectorError> {
+    let mut stats = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(device) = parts.next() else {
+            continue;
+        };
+        let mut stat = CgroupIoStat {
+            device: device.to_string(),
+            ..Default::default()
+        };
+        for part in parts {
+            let Some((key, raw)) = part.split_once('=') else {
+                continue;
+            };
+            let target = match key {
+                "rbytes" => &mut stat.rbytes,
+                "wbytes" => &mut stat.wbytes,
+                "rios" => &mut stat.rios,
+                "wios" => &mut stat.wios,
+                _ => continue,
+            };
+            *target = raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+                path: path.to_string(),
+                field: format!("{} for {}", key, device),
+                raw: raw.to_string(),
+            })?;
+        }
+        stats.push(stat);
+    }
+    Ok(stats)
+}
+
+/// Statistics of a single cgroup. Each controller file is optional: the
+/// root cgroup has no `memory.current`, and controllers may not be enabled
+/// in a subtree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CgroupStats {
+    /// Path relative to the hierarchy root, e.g. `/system.slice`.
+    pub cgroup: String,
+    pub cpu_stat: HashMap<String, u64>,
+    pub memory_current: Option<u64>,
+    /// `None` if unlimited (`max`) or unavailable.
+    pub memory_max: Option<u64>,
+    pub memory_events: HashMap<String, u64>,
+    pub io_stat: Vec<CgroupIoStat>,
+    pub pids_current: Option<u64>,
+}
+
+/// Read the controller files of one cgroup directory. Files that cannot be
+/// read are skipped, as the cgroup may be removed while it is being read.
+pub fn read_cgroup(dir: &Path, cgroup: &str) -> Result<CgroupStats, CollectorError> {
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+    let path_of = |file: &str| dir.join(file).display().to_string();
+    let value = |file: &str| read(file).and_then(|s| s.trim().parse::<u64>().ok());
+
+    Ok(CgroupStats {
+        cgroup: cgroup.to_string(),
+        cpu_stat: match read("cpu.stat") {
+            Some(content) => parse_flat_keyed(&content, &path_of("cpu.stat"))?,
+            None => HashMap::new(),
+        },
+        memory_current: value("memory.current"),
+        memory_max: value("memory.max"),
+        memory_events: match read("memory.events") {
+            Some(content) => parse_flat_keyed(&content, &path_of("memory.events"))?,
+            None => HashMap::new(),
+        },
+        io_stat: match read("io.stat") {
+            Some(content) => parse_io_stat(&content, &path_of("io.stat"))?,
+            None => Vec::new(),
+        },
+        pids_current: value("pids.current"),
+    })
+}
+
+pub struct CgroupCollector {
+    include_pattern: Regex,
+    exclude_pattern: Regex,
+    max_depth: usize,
+}
+
+impl CgroupCollector {
+    /// `max_depth` 0 reports only the root cgroup, 1 its direct children
+    /// (e.g. `/system.slice`) and so on.
+    pub fn new(
+        include_pattern: &str,
+        exclude_pattern: &str,
+        max_depth: usize,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            include_pattern: Regex::new(include_pattern)?,
+            exclude_pattern: Regex::new(exclude_pattern)?,
+            max_depth,
+        })
+    }
+
+    fn is_included(&self, cgroup: &str) -> bool {
+        self.include_pattern.is_match(cgroup) && !self.exclude_pattern.is_match(cgroup)
+    }
+
+    /// Depth-first walk in sorted order. Excluded cgroups are not reported,
+    /// but their children are still visited.
+    fn walk(
+        &self,
+        dir: &Path,
+        cgroup: &str,
+        depth: usize,
+        out: &mut Vec<CgroupStats>,
+    ) -> Result<(), CollectorError> {
+        if self.is_included(cgroup) {
+            out.push(read_cgroup(dir, cgroup)?);
+        }
+        if depth >= self.max_depth {
+            return Ok(());
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(());
+        };
+        let mut children: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+        children.sort();
+        for child in children {
+            let child_cgroup = if cgroup == "/" {
+                format!("/{}", child)
+            } else {
+                format!("{}/{}", cgroup, child)
+            };
+            self.walk(&dir.join(&child), &child_cgroup, depth + 1, out)?;
+        }
+        Ok(())
+    }
+}
+
+impl Collector for CgroupCollector {
+    fn name(&self) -> &'static str {
+        "cgroup"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(SYS_FS_CGROUP_PATH))
+    }
+}
+
+impl CgroupCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        // Only the unified (v2) hierarchy has cgroup.controllers at its root.
+        if !root.join("cgroup.controllers").exists() {
+            return Err(CollectorError::NotSupported {
+                path: root.display().to_string(),
+            });
+        }
+        let mut cgroups = Vec::new();
+        self.walk(root, "/", 0, &mut cgroups)?;
+        Ok(cgroup_metrics(&cgroups))
+    }
+}
+
+/// Build metric families from the collected cgroups.
+pub fn cgroup_metrics(cgroups: &[CgroupStats]) -> Vec<Metric> {
+    let cgroup_label = |c: &CgroupStats| vec![("cgroup".to_string(), c.cgroup.clone())];
+
+    type MetricDef = (
+        &'static str,
+        &'static str,
+        MetricType,
+        Box<dyn Fn(&CgroupStats) -> Option<f64>>,
+    );
+    let usec = |key: &'static str| {
+        move |c: &CgroupStats| {
+            c.cpu_stat
+                .get(key)
+                .map(|v| *v as f64 / MICROSECONDS_PER_SECOND)
+        }
+    };
+    let metric_defs: Vec<MetricDef> = vec![
+        (
+            "sysmetrics_cgroup_cpu_usage_seconds_total",
+            "Total CPU time consumed by the cgroup in seconds.",
+            MetricType::Counter,
+            Box::new(usec("usage_usec")),
+        ),
+        (
+            "sysmetrics_cgroup_cpu_user_seconds_total",
+            "User CPU time consumed by the cgroup in seconds.",
+            MetricType::Counter,
+            Box::new(usec("user_usec")),
+        ),
+        (
+            "sysmetrics_cgroup_cpu_system_seconds_total",
+            "System CPU time consumed by the cgroup in seconds.",
+            MetricType::Counter,
+            Box::new(usec("system_usec")),
+        ),
+        (
+            "sysmetrics_cgroup_cpu_periods_total",
+            "Number of elapsed CPU bandwidth enforcement periods.",
+            MetricType::Counter,
+            Box::new(|c: &CgroupStats| c.cpu_stat.get("nr_periods").map(|v| *v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_cpu_throttled_periods_total",
+            "Number of CPU bandwidth periods in which the cgroup was throttled.",
+            MetricType::Counter,
+            Box::new(|c: &CgroupStats| c.cpu_stat.get("nr_throttled").map(|v| *v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_cpu_throttled_seconds_total",
+            "Total time the cgroup was throttled in seconds.",
+            MetricType::Counter,
+            Box::new(usec("throttled_usec")),
+        ),
+        (
+            "sysmetrics_cgroup_memory_current_bytes",
+            "Memory currently used by the cgroup in bytes.",
+            MetricType::Gauge,
+            Box::new(|c: &CgroupStats| c.memory_current.map(|v| v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_memory_max_bytes",
+            "Memory limit of the cgroup in bytes. Absent if unlimited.",
+            MetricType::Gauge,
+            Box::new(|c: &CgroupStats| c.memory_max.map(|v| v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_memory_oom_total",
+            "Number of times the cgroup's memory usage reached the limit and allocation failed.",
+            MetricType::Counter,
+            Box::new(|c: &CgroupStats| c.memory_events.get("oom").map(|v| *v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_memory_oom_kill_total",
+            "Number of processes in the cgroup killed by the OOM killer.",
+            MetricType::Counter,
+            Box::new(|c: &CgroupStats| c.memory_events.get("oom_kill").map(|v| *v as f64)),
+        ),
+        (
+            "sysmetrics_cgroup_pids_current",
+            "Number of processes in the cgroup.",
+            MetricType::Gauge,
+            Box::new(|c: &CgroupStats| c.pids_current.map(|v| v as f64)),
+        ),
+    ];
+
+    let mut metrics: Vec<Metric> = metric_defs
+        .iter()
+        .map(|(name, help, metric_type, value_fn)| Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: *metric_type,
+            samples: cgroups
+                .iter()
+                .filter_map(|c| {
+                    value_fn(c).map(|value| MetricSample {
+                        labels: cgroup_label(c),
+                        value,
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    type IoDef = (&'static str, &'static str, fn(&CgroupIoStat) -> u64);
+    let io_defs: [IoDef; 4] = [
+        (
+            "sysmetrics_cgroup_io_read_bytes_total",
+            "Bytes read by the cgroup per device.",
+            |s| s.rbytes,
+        ),
+        (
+            "sysmetrics_cgroup_io_written_bytes_total",
+            "Bytes written by the cgroup per device.",
+            |s| s.wbytes,
+        ),
+        (
+            "sysmetrics_cgroup_io_reads_total",
+            "Read operations issued by the cgroup per device.",
+            |s| s.rios,
+        ),
+        (
+            "sysmetrics_cgroup_io_writes_total",
+            "Write operations issued by the cgroup per device.",
+            |s| s.wios,
+        ),
+    ];
+    for (name, help, value_fn) in io_defs {
+        metrics.push(Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Counter,
+            samples: cgroups
+                .iter()
+                .flat_map(|c| {
+                    c.io_stat.iter().map(move |s| {
+                        let mut labels = cgroup_label(c);
+                        labels.push(("device".to_string(), s.device.clone()));
+                        MetricSample {
+                            labels,
+                            value: value_fn(s) as f64,
+                        }
+                    })
+                })
+                .collect(),
+        });
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const CGROUP_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/fs/cgroup");
+
+    fn cgroups(metric: &Metric) -> Vec<&str> {
+        metric
+            .samples
+            .iter()
+            .map(|s| s.labels[0].1.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_io_stat() {
+        let content = "8:0 rbytes=4096 wbytes=8192 rios=1 wios=2 dbytes=0 dios=0\n\
+                       253:1 rbytes=1 wbytes=2 rios=3 wios=4 cost.vrate=100.00\n";
+        let stats = parse_io_stat(content, "io.stat").unwrap();
+        assert_eq!(
+            stats[0],
+            CgroupIoStat {
+                device: "8:0".to_string(),
+                rbytes: 4096,
+                wbytes: 8192,
+                rios: 1,
+                wios: 2,
+            }
+        );
+        assert_eq!(stats[1].wios, 4);
+        assert!(parse_io_stat("8:0 rbytes=x\n", "io.stat").is_err());
+    }
+
+    #[test]
+    fn test_cgroup_collector_walk_depth_and_exclude() {
+        let collector = CgroupCollector::new("", r"\.mount$", 2).unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(CGROUP_FIXTURE_DIR))
+            .unwrap();
+        // worker/ is at depth 3; home.mount is excluded.
+        let pids = find_metric(&metrics, "sysmetrics_cgroup_pids_current");
+        assert_eq!(
+            cgroups(pids),
+            vec![
+                "/system.slice",
+                "/system.slice/nginx.service",
+                "/user.slice"
+            ]
+        );
+        assert_eq!(pids.samples[2].value, 42.0);
+
+        let shallow = CgroupCollector::new("", r"\.mount$", 1).unwrap();
+        let metrics = shallow
+            .collect_from_dir(Path::new(CGROUP_FIXTURE_DIR))
+            .unwrap();
+        let pids = find_metric(&metrics, "sysmetrics_cgroup_pids_current");
+        assert_eq!(cgroups(pids), vec!["/system.slice", "/user.slice"]);
+    }
+
+    #[test]
+    fn test_cgroup_collector_include_pattern() {
+        let collector = CgroupCollector::new(r"\.service$", "^$", 5).unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(CGROUP_FIXTURE_DIR))
+            .unwrap();
+        let usage = find_metric(&metrics, "sysmetrics_cgroup_cpu_usage_seconds_total");
+        assert_eq!(cgroups(usage), vec!["/system.slice/nginx.service"]);
+    }
+
+    #[test]
+    fn test_cgroup_collector_cpu_and_memory() {
+        let collector = CgroupCollector::new("", r"\.mount$", 2).unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(CGROUP_FIXTURE_DIR))
+            .unwrap();
+
+        let usage = find_metric(&metrics, "sysmetrics_cgroup_cpu_usage_seconds_total");
+        assert_eq!(usage.metric_type, MetricType::Counter);
+        assert_eq!(
+            cgroups(usage),
+            vec!["/", "/system.slice", "/system.slice/nginx.service"]
+        );
+        assert_eq!(usage.samples[2].value, 1.5);
+
+        let throttled = find_metric(&metrics, "sysmetrics_cgroup_cpu_throttled_periods_total");
+        assert_eq!(throttled.samples[2].value, 25.0);
+        let throttled_secs = find_metric(&metrics, "sysmetrics_cgroup_cpu_throttled_seconds_total");
+        assert_eq!(throttled_secs.samples[2].value, 2.5);
+
+        // The root cgroup has no memory.current.
+        let current = find_metric(&metrics, "sysmetrics_cgroup_memory_current_bytes");
+        assert_eq!(
+            cgroups(current),
+            vec!["/system.slice", "/system.slice/nginx.service"]
+        );
+
+        // "max" (unlimited) has no sample.
+        let max = find_metric(&metrics, "sysmetrics_cgroup_memory_max_bytes");
+        assert_eq!(cgroups(max), vec!["/system.slice/nginx.service"]);
+        assert_eq!(max.samples[0].value, 536870912.0);
+
+        let oom_kill = find_metric(&metrics, "sysmetrics_cgroup_memory_oom_kill_total");
+        assert_eq!(oom_kill.samples[1].value, 2.0);
+    }
+
+    #[test]
+    fn test_cgroup_collector_io() {
+        let collector = CgroupCollector::new("", r"\.mount$", 2).unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(CGROUP_FIXTURE_DIR))
+            .unwrap();
+        let read = find_metric(&metrics, "sysmetrics_cgroup_io_read_bytes_total");
+        assert_eq!(read.samples.len(), 4);
+        assert_eq!(
+            read.samples[1].labels,
+            vec![
+                ("cgroup".to_string(), "/".to_string()),
+                ("device".to_string(), "259:0".to_string()),
+            ]
+        );
+        assert_eq!(read.samples[1].value, 2048.0);
+        let writes = find_metric(&metrics, "sysmetrics_cgroup_io_writes_total");
+        assert_eq!(writes.samples[3].value, 20.0);
+    }
+
+    #[test]
+    fn test_cgroup_collector_v1_not_supported() {
+        let collector = CgroupCollector::new("", "^$", 2).unwrap();
+        assert_missing_root_not_supported(|root| collector.collect_from_dir(root));
+    }
+}
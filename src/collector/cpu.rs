@@ -3,52 +3,89 @@ use crate::error::CollectorError;
 use std::fs;
 
 const PROC_STAT_PATH: &str = "/proc/stat";
-const USER_HZ: f64 = 100.0;
+pub const USER_HZ: f64 = 100.0;
 
+/// Columns of a `cpu` line in /proc/stat, in USER_HZ. `guest` and
+/// `guest_nice` (time spent running a virtual CPU for guest operating
+/// systems) are already included in `user` and `nice` respectively.
+const CPU_COLUMNS: &[&str] = &[
+    "user",
+    "nice",
+    "system",
+    "idle",
+    "iowait",
+    "irq",
+    "softirq",
+    "steal",
+    "guest",
+    "guest_nice",
+];
+
+/// Modes exported in `sysmetrics_cpu_seconds_total`. The guest columns are
+/// exported separately so that summing over modes does not count guest
+/// time twice.
 const CPU_MODES: &[&str] = &[
     "user", "nice", "system", "idle", "iowait", "irq", "softirq", "steal",
 ];
 
-/// Parsed CPU statistics for a single core.
+/// Guest columns (indices into `CPU_COLUMNS`) and the mode label they are
+/// exported under in `sysmetrics_cpu_guest_seconds_total`.
+const GUEST_MODES: &[(usize, &str)] = &[(8, "user"), (9, "nice")];
+
+/// Softirq types in the order of the per-type columns of the `softirq` line
+/// (see `softirq_to_name` in kernel/softirq.c).
+const SOFTIRQ_TYPES: &[&str] = &[
+    "hi", "timer", "net_tx", "net_rx", "block", "irq_poll", "tasklet", "sched", "hrtimer", "rcu",
+];
+
+/// Parsed CPU statistics for a single core, or for all cores combined when
+/// parsed from the aggregate `cpu` line (`cpu_id` is then empty).
 #[derive(Debug, Clone)]
 pub struct CpuStats {
     pub cpu_id: String,
+    /// Values in `CPU_COLUMNS` order. Older kernels report fewer than ten
+    /// columns (guest was added in 2.6.24, guest_nice in 2.6.33).
     pub values: Vec<u64>,
 }
 
+/// Parse a single `cpu`/`cpuN` line of /proc/stat.
+fn parse_cpu_line(line: &str) -> Result<CpuStats, CollectorError> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 9 {
+        return Err(CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: "cpu line".to_string(),
+            raw: line.to_string(),
+        });
+    }
+    let cpu_id = parts[0]
+        .strip_prefix("cpu")
+        .ok_or_else(|| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: "cpu id".to_string(),
+            raw: parts[0].to_string(),
+        })?
+        .to_string();
+
+    let mut values = Vec::new();
+    for (i, part) in parts[1..].iter().enumerate().take(CPU_COLUMNS.len()) {
+        let v = part.parse::<u64>().map_err(|_| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: format!("cpu{} column {}", cpu_id, i),
+            raw: part.to_string(),
+        })?;
+        values.push(v);
+    }
+    Ok(CpuStats { cpu_id, values })
+}
+
 /// Parse /proc/stat content into per-CPU statistics.
 pub fn parse_cpu_stats(content: &str) -> Result<Vec<CpuStats>, CollectorError> {
     let mut stats = Vec::new();
     for line in content.lines() {
         // Match lines like "cpu0 ..." but not the aggregate "cpu ..." line
         if line.starts_with("cpu") && !line.starts_with("cpu ") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 9 {
-                return Err(CollectorError::Parse {
-                    path: PROC_STAT_PATH.to_string(),
-                    field: "cpu line".to_string(),
-                    raw: line.to_string(),
-                });
-            }
-            let cpu_id = parts[0]
-                .strip_prefix("cpu")
-                .ok_or_else(|| CollectorError::Parse {
-                    path: PROC_STAT_PATH.to_string(),
-                    field: "cpu id".to_string(),
-                    raw: parts[0].to_string(),
-                })?
-                .to_string();
-
-            let mut values = Vec::new();
-            for (i, part) in parts[1..].iter().enumerate().take(8) {
-                let v = part.parse::<u64>().map_err(|_| CollectorError::Parse {
-                    path: PROC_STAT_PATH.to_string(),
-                    field: format!("cpu{} column {}", cpu_id, i),
-                    raw: part.to_string(),
-                })?;
-                values.push(v);
-            }
-            stats.push(CpuStats { cpu_id, values });
+            stats.push(parse_cpu_line(line)?);
         }
     }
     if stats.is_empty() {
@@ -61,7 +98,90 @@ pub fn parse_cpu_stats(content: &str) -> Result<Vec<CpuStats>, CollectorError> {
     Ok(stats)
 }
 
-pub struct CpuCollector;
+/// Parse the aggregate `cpu ` line of /proc/stat (the sum over all CPUs).
+pub fn parse_cpu_aggregate(content: &str) -> Result<CpuStats, CollectorError> {
+    let line = content
+        .lines()
+        .find(|l| l.starts_with("cpu "))
+        .ok_or_else(|| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: "cpu line".to_string(),
+            raw: "aggregate cpu line not found".to_string(),
+        })?;
+    parse_cpu_line(line)
+}
+
+/// Kernel-wide counters from the non-cpu lines of /proc/stat. Fields are
+/// `None` when the line is absent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KernelStats {
+    pub context_switches: Option<u64>,
+    pub interrupts: Option<u64>,
+    pub softirqs: Option<u64>,
+    pub softirqs_by_type: Vec<(String, u64)>,
+    pub forks: Option<u64>,
+    pub procs_running: Option<u64>,
+    pub procs_blocked: Option<u64>,
+}
+
+/// Parse the kernel-wide counters (`ctxt`, `intr`, `softirq`, `processes`,
+/// `procs_running`, `procs_blocked`) from /proc/stat content.
+pub fn parse_kernel_stats(content: &str) -> Result<KernelStats, CollectorError> {
+    let parse_value = |field: &str, raw: &str| -> Result<u64, CollectorError> {
+        raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: field.to_string(),
+            raw: raw.to_string(),
+        })
+    };
+
+    let mut stats = KernelStats::default();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(key) = parts.next() else {
+            continue;
+        };
+        let target = match key {
+            "ctxt" => &mut stats.context_switches,
+            "intr" => &mut stats.interrupts,
+            "softirq" => &mut stats.softirqs,
+            "processes" => &mut stats.forks,
+            "procs_running" => &mut stats.procs_running,
+            "procs_blocked" => &mut stats.procs_blocked,
+            _ => continue,
+        };
+        // `intr` and `softirq` are followed by per-source counts; the
+        // first value is the total.
+        let raw = parts.next().ok_or_else(|| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: key.to_string(),
+            raw: line.to_string(),
+        })?;
+        *target = Some(parse_value(key, raw)?);
+
+        if key == "softirq" {
+            for (softirq_type, raw) in SOFTIRQ_TYPES.iter().zip(parts) {
+                let value = parse_value(&format!("softirq {}", softirq_type), raw)?;
+                stats
+                    .softirqs_by_type
+                    .push((softirq_type.to_string(), value));
+            }
+        }
+    }
+    Ok(stats)
+}
+
+pub struct CpuCollector {
+    aggregate: bool,
+}
+
+impl CpuCollector {
+    /// With `aggregate`, the all-CPU totals from the `cpu` line are also
+    /// exported, without a `cpu` label.
+    pub fn new(aggregate: bool) -> Self {
+        Self { aggregate }
+    }
+}
 
 impl Collector for CpuCollector {
     fn name(&self) -> &'static str {
@@ -77,27 +197,47 @@ impl Collector for CpuCollector {
     }
 }
 
+/// Build `mode`-labelled samples (in seconds) for the given columns of a
+/// `cpu` line, after the labels in `labels`.
+fn mode_samples(
+    stat: &CpuStats,
+    modes: &[(usize, &str)],
+    labels: &[(String, String)],
+) -> Vec<MetricSample> {
+    modes
+        .iter()
+        .filter_map(|&(i, mode)| {
+            stat.values.get(i).map(|v| {
+                let mut labels = labels.to_vec();
+                labels.push(("mode".to_string(), mode.to_string()));
+                MetricSample {
+                    labels,
+                    value: *v as f64 / USER_HZ,
+                }
+            })
+        })
+        .collect()
+}
+
 impl CpuCollector {
     pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
+        // CPU and kernel-wide stats come from the same read of /proc/stat so
+        // they describe one consistent snapshot.
         let stats = parse_cpu_stats(content)?;
+        let kernel = parse_kernel_stats(content)?;
         let cpu_count = stats.len();
 
+        let cpu_modes: Vec<(usize, &str)> = CPU_MODES.iter().copied().enumerate().collect();
+
         let mut samples = Vec::new();
+        let mut guest_samples = Vec::new();
         for stat in &stats {
-            for (i, mode) in CPU_MODES.iter().enumerate() {
-                if i < stat.values.len() {
-                    samples.push(MetricSample {
-                        labels: vec![
-                            ("cpu".to_string(), stat.cpu_id.clone()),
-                            ("mode".to_string(), mode.to_string()),
-                        ],
-                        value: stat.values[i] as f64 / USER_HZ,
-                    });
-                }
-            }
+            let cpu_label = [("cpu".to_string(), stat.cpu_id.clone())];
+            samples.extend(mode_samples(stat, &cpu_modes, &cpu_label));
+            guest_samples.extend(mode_samples(stat, GUEST_MODES, &cpu_label));
         }
 
-        Ok(vec![
+        let mut metrics = vec![
             Metric {
                 name: "sysmetrics_cpu_seconds_total".to_string(),
                 help: "Total CPU time spent in each mode.".to_string(),
@@ -113,13 +253,109 @@ impl CpuCollector {
                     value: cpu_count as f64,
                 }],
             },
-        ])
+        ];
+
+        if !guest_samples.is_empty() {
+            metrics.push(Metric {
+                name: "sysmetrics_cpu_guest_seconds_total".to_string(),
+                help: "Total CPU time spent running guests (already included in the user and nice modes of sysmetrics_cpu_seconds_total).".to_string(),
+                metric_type: MetricType::Counter,
+                samples: guest_samples,
+            });
+        }
+
+        if self.aggregate {
+            let total = parse_cpu_aggregate(content)?;
+            metrics.push(Metric {
+                name: "sysmetrics_cpu_aggregate_seconds_total".to_string(),
+                help: "Total CPU time spent in each mode, summed over all CPUs.".to_string(),
+                metric_type: MetricType::Counter,
+                samples: mode_samples(&total, &cpu_modes, &[]),
+            });
+            metrics.push(Metric {
+                name: "sysmetrics_cpu_aggregate_guest_seconds_total".to_string(),
+                help: "Total CPU time spent running guests, summed over all CPUs.".to_string(),
+                metric_type: MetricType::Counter,
+                samples: mode_samples(&total, GUEST_MODES, &[]),
+            });
+        }
+
+        let kernel_metrics = [
+            (
+                "sysmetrics_context_switches_total",
+                "Total number of context switches.",
+                MetricType::Counter,
+                kernel.context_switches,
+            ),
+            (
+                "sysmetrics_interrupts_total",
+                "Total number of interrupts serviced.",
+                MetricType::Counter,
+                kernel.interrupts,
+            ),
+            (
+                "sysmetrics_softirqs_total",
+                "Total number of softirqs serviced.",
+                MetricType::Counter,
+                kernel.softirqs,
+            ),
+            (
+                "sysmetrics_forks_total",
+                "Total number of forks since boot.",
+                MetricType::Counter,
+                kernel.forks,
+            ),
+            (
+                "sysmetrics_procs_running",
+                "Number of processes in runnable state.",
+                MetricType::Gauge,
+                kernel.procs_running,
+            ),
+            (
+                "sysmetrics_procs_blocked",
+                "Number of processes blocked waiting for I/O.",
+                MetricType::Gauge,
+                kernel.procs_blocked,
+            ),
+        ];
+        for (name, help, metric_type, value) in kernel_metrics {
+            if let Some(value) = value {
+                metrics.push(Metric {
+                    name: name.to_string(),
+                    help: help.to_string(),
+                    metric_type,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value: value as f64,
+                    }],
+                });
+            }
+        }
+
+        if !kernel.softirqs_by_type.is_empty() {
+            metrics.push(Metric {
+                name: "sysmetrics_softirqs_by_type_total".to_string(),
+                help: "Total number of softirqs serviced by type.".to_string(),
+                metric_type: MetricType::Counter,
+                samples: kernel
+                    .softirqs_by_type
+                    .iter()
+                    .map(|(softirq_type, value)| MetricSample {
+                        labels: vec![("type".to_string(), softirq_type.clone())],
+                        value: *value as f64,
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(metrics)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collector::find_metric;
 
     const PROC_STAT_FIXTURE: &str = "\
 cpu  74156 1260 22706 6316498 4539 0 456 0 0 0
@@ -182,9 +418,10 @@ cpu0 10000 200 3000 500000 100 0 50 0 0 0
 
     #[test]
     fn test_cpu_collector_metrics() {
-        let collector = CpuCollector;
+        let collector = CpuCollector::new(false);
         let metrics = collector.collect_from_string(PROC_STAT_FIXTURE).unwrap();
-        assert_eq!(metrics.len(), 2);
+        // cpu seconds, cpu count, guest seconds and the interrupt total
+        assert_eq!(metrics.len(), 4);
 
         let cpu_seconds = &metrics[0];
         assert_eq!(cpu_seconds.name, "sysmetrics_cpu_seconds_total");
@@ -206,4 +443,153 @@ cpu0 10000 200 3000 500000 100 0 50 0 0 0
         assert_eq!(cpu_count.metric_type, MetricType::Gauge);
         assert_eq!(cpu_count.samples[0].value, 2.0);
     }
+
+    const PROC_STAT_FULL: &str = "\
+cpu  74156 1260 22706 6316498 4539 0 456 0 0 0
+cpu0 18539 315 5676 1579124 1134 0 114 0 0 0
+intr 167757 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 1 1 1 0 0 0 0 275 51
+ctxt 399888
+btime 1792178020
+processes 14489
+procs_running 2
+procs_blocked 1
+softirq 84042 0 37206 2 3458 0 0 1 0 19 43356
+";
+
+    #[test]
+    fn test_parse_kernel_stats() {
+        let stats = parse_kernel_stats(PROC_STAT_FULL).unwrap();
+        assert_eq!(stats.context_switches, Some(399888));
+        assert_eq!(stats.interrupts, Some(167757));
+        assert_eq!(stats.softirqs, Some(84042));
+        assert_eq!(stats.forks, Some(14489));
+        assert_eq!(stats.procs_running, Some(2));
+        assert_eq!(stats.procs_blocked, Some(1));
+        assert_eq!(stats.softirqs_by_type.len(), 10);
+        assert_eq!(stats.softirqs_by_type[1], ("timer".to_string(), 37206));
+        assert_eq!(stats.softirqs_by_type[9], ("rcu".to_string(), 43356));
+    }
+
+    #[test]
+    fn test_parse_kernel_stats_missing_lines() {
+        let stats = parse_kernel_stats(PROC_STAT_SINGLE_CPU).unwrap();
+        assert_eq!(stats, KernelStats::default());
+    }
+
+    #[test]
+    fn test_parse_kernel_stats_malformed() {
+        assert!(parse_kernel_stats("ctxt abc\n").is_err());
+        assert!(parse_kernel_stats("procs_running\n").is_err());
+    }
+
+    #[test]
+    fn test_cpu_collector_kernel_metrics() {
+        let collector = CpuCollector::new(false);
+        let metrics = collector.collect_from_string(PROC_STAT_FULL).unwrap();
+
+        let ctxt = find_metric(&metrics, "sysmetrics_context_switches_total");
+        assert_eq!(ctxt.metric_type, MetricType::Counter);
+        assert_eq!(ctxt.samples[0].value, 399888.0);
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_interrupts_total").samples[0].value,
+            167757.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_forks_total").samples[0].value,
+            14489.0
+        );
+
+        let blocked = find_metric(&metrics, "sysmetrics_procs_blocked");
+        assert_eq!(blocked.metric_type, MetricType::Gauge);
+        assert_eq!(blocked.samples[0].value, 1.0);
+
+        let by_type = find_metric(&metrics, "sysmetrics_softirqs_by_type_total");
+        assert_eq!(by_type.samples.len(), 10);
+        assert_eq!(
+            by_type.samples[3].labels,
+            vec![("type".to_string(), "net_rx".to_string())]
+        );
+        assert_eq!(by_type.samples[3].value, 3458.0);
+    }
+
+    const PROC_STAT_GUEST: &str = "\
+cpu  3000 200 1000 50000 100 0 50 10 1200 80
+cpu0 1500 100 500 25000 50 0 25 5 600 40
+cpu1 1500 100 500 25000 50 0 25 5 600 40
+";
+
+    #[test]
+    fn test_parse_cpu_stats_guest_columns() {
+        let stats = parse_cpu_stats(PROC_STAT_GUEST).unwrap();
+        assert_eq!(stats[0].values.len(), 10);
+        assert_eq!(stats[0].values[8], 600); // guest
+        assert_eq!(stats[0].values[9], 40); // guest_nice
+    }
+
+    #[test]
+    fn test_parse_cpu_aggregate() {
+        let total = parse_cpu_aggregate(PROC_STAT_GUEST).unwrap();
+        assert_eq!(total.cpu_id, "");
+        assert_eq!(total.values[0], 3000);
+        assert_eq!(total.values[8], 1200);
+        assert!(parse_cpu_aggregate("cpu0 1 2 3 4 5 6 7 8\n").is_err());
+    }
+
+    #[test]
+    fn test_cpu_collector_guest_metrics() {
+        let collector = CpuCollector::new(false);
+        let metrics = collector.collect_from_string(PROC_STAT_GUEST).unwrap();
+        let cpu_seconds = &metrics[0];
+        // guest time is not a separate mode of cpu_seconds_total
+        assert_eq!(cpu_seconds.samples.len(), 16);
+
+        let guest = metrics
+            .iter()
+            .find(|m| m.name == "sysmetrics_cpu_guest_seconds_total")
+            .unwrap();
+        assert_eq!(guest.metric_type, MetricType::Counter);
+        assert_eq!(guest.samples.len(), 4);
+        assert_eq!(
+            guest.samples[1].labels,
+            vec![
+                ("cpu".to_string(), "0".to_string()),
+                ("mode".to_string(), "nice".to_string()),
+            ]
+        );
+        assert!((guest.samples[0].value - 6.0).abs() < 0.001);
+        assert!((guest.samples[1].value - 0.4).abs() < 0.001);
+
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name == "sysmetrics_cpu_aggregate_seconds_total"));
+    }
+
+    #[test]
+    fn test_cpu_collector_old_kernel_without_guest() {
+        let input = "cpu  100 0 50 1000 0 0 0 0\ncpu0 100 0 50 1000 0 0 0 0\n";
+        let collector = CpuCollector::new(false);
+        let metrics = collector.collect_from_string(input).unwrap();
+        assert_eq!(metrics[0].samples.len(), 8);
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name == "sysmetrics_cpu_guest_seconds_total"));
+    }
+
+    #[test]
+    fn test_cpu_collector_aggregate() {
+        let collector = CpuCollector::new(true);
+        let metrics = collector.collect_from_string(PROC_STAT_GUEST).unwrap();
+
+        let total = find_metric(&metrics, "sysmetrics_cpu_aggregate_seconds_total");
+        assert_eq!(total.samples.len(), 8);
+        assert_eq!(
+            total.samples[0].labels,
+            vec![("mode".to_string(), "user".to_string())]
+        );
+        assert!((total.samples[0].value - 30.0).abs() < 0.001);
+
+        let guest = find_metric(&metrics, "sysmetrics_cpu_aggregate_guest_seconds_total");
+        assert_eq!(guest.samples.len(), 2);
+        assert!((guest.samples[0].value - 12.0).abs() < 0.001);
+    }
 }
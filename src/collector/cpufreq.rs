@@ -0,0 +1,288 @@
+use crate::collector::{
+    list_sysfs_dir, read_sysfs_string, read_sysfs_value, Collector, Metric, MetricSample,
+    MetricType,
+};
+use crate::error::CollectorError;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SYS_CPU_PATH: &str = "/sys/devices/system/cpu";
+const HERTZ_PER_KILOHERTZ: f64 = 1000.0;
+
+/// Frequency attributes under `cpuN/cpufreq/`, all reported by sysfs in kHz.
+const FREQUENCY_DEFS: &[(&str, &str, &str)] = &[
+    (
+        "scaling_cur_freq",
+        "sysmetrics_cpu_scaling_frequency_hertz",
+        "Current scaled CPU frequency in hertz.",
+    ),
+    (
+        "scaling_min_freq",
+        "sysmetrics_cpu_scaling_frequency_min_hertz",
+        "Minimum scaled CPU frequency in hertz.",
+    ),
+    (
+        "scaling_max_freq",
+        "sysmetrics_cpu_scaling_frequency_max_hertz",
+        "Maximum scaled CPU frequency in hertz.",
+    ),
+    (
+        "cpuinfo_cur_freq",
+        "sysmetrics_cpu_frequency_hertz",
+        "Current CPU frequency reported by the hardware in hertz.",
+    ),
+    (
+        "cpuinfo_min_freq",
+        "sysmetrics_cpu_frequency_min_hertz",
+        "Minimum CPU frequency supported by the hardware in hertz.",
+    ),
+    (
+        "cpuinfo_max_freq",
+        "sysmetrics_cpu_frequency_max_hertz",
+        "Maximum CPU frequency supported by the hardware in hertz.",
+    ),
+];
+
+/// Frequency scaling state of a single logical CPU. Attributes that are
+/// absent or unreadable (e.g. `cpuinfo_cur_freq` is root-only) are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuFreq {
+    pub cpu_id: String,
+    /// Frequencies in kHz, in `FREQUENCY_DEFS` order.
+    pub frequencies_khz: Vec<Option<u64>>,
+    pub governor: Option<String>,
+    pub driver: Option<String>,
+    pub core_throttle_count: Option<u64>,
+    pub package_throttle_count: Option<u64>,
+    pub package_id: Option<String>,
+}
+
+/// Read the cpufreq, thermal_throttle and topology attributes of a `cpuN`
+/// directory.
+pub fn read_cpu_freq(dir: &Path, cpu_id: &str) -> CpuFreq {
+    let cpufreq = dir.join("cpufreq");
+    let throttle = dir.join("thermal_throttle");
+    CpuFreq {
+        cpu_id: cpu_id.to_string(),
+        frequencies_khz: FREQUENCY_DEFS
+            .iter()
+            .map(|(file, _, _)| read_sysfs_value(&cpufreq.join(file)))
+            .collect(),
+        governor: read_sysfs_string(&cpufreq.join("scaling_governor")),
+        driver: read_sysfs_string(&cpufreq.join("scaling_driver")),
+        core_throttle_count: read_sysfs_value(&throttle.join("core_throttle_count")),
+        package_throttle_count: read_sysfs_value(&throttle.join("package_throttle_count")),
+        package_id: read_sysfs_string(&dir.join("topology/physical_package_id")),
+    }
+}
+
+pub struct CpuFreqCollector;
+
+impl Collector for CpuFreqCollector {
+    fn name(&self) -> &'static str {
+        "cpufreq"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(SYS_CPU_PATH))
+    }
+}
+
+impl CpuFreqCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        // `cpu` also matches siblings such as `cpufreq` and `cpuidle`.
+        let cpus: Vec<CpuFreq> = list_sysfs_dir(root, "cpu")?
+            .iter()
+            .filter_map(|dir| {
+                let name = dir.file_name()?.to_str()?;
+                let cpu_id = name.strip_prefix("cpu")?;
+                if cpu_id.is_empty() || !cpu_id.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                Some(read_cpu_freq(dir, cpu_id))
+            })
+            .collect();
+
+        let cpu_label = |c: &CpuFreq| vec![("cpu".to_string(), c.cpu_id.clone())];
+
+        let mut metrics: Vec<Metric> = FREQUENCY_DEFS
+            .iter()
+            .enumerate()
+            .map(|(i, (_, name, help))| Metric {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric_type: MetricType::Gauge,
+                samples: cpus
+                    .iter()
+                    .filter_map(|c| {
+                        c.frequencies_khz[i].map(|khz| MetricSample {
+                            labels: cpu_label(c),
+                            value: khz as f64 * HERTZ_PER_KILOHERTZ,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        metrics.push(Metric {
+            name: "sysmetrics_cpu_scaling_info".to_string(),
+            help: "CPU frequency scaling governor and driver.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: cpus
+                .iter()
+                .filter(|c| c.governor.is_some() || c.driver.is_some())
+                .map(|c| {
+                    let mut labels = cpu_label(c);
+                    labels.push((
+                        "governor".to_string(),
+                        c.governor.clone().unwrap_or_default(),
+                    ));
+                    labels.push(("driver".to_string(), c.driver.clone().unwrap_or_default()));
+                    MetricSample { labels, value: 1.0 }
+                })
+                .collect(),
+        });
+
+        metrics.push(Metric {
+            name: "sysmetrics_cpu_core_throttles_total".to_string(),
+            help: "Number of times the CPU core was throttled due to high temperature.".to_string(),
+            metric_type: MetricType::Counter,
+            samples: cpus
+                .iter()
+                .filter_map(|c| {
+                    c.core_throttle_count.map(|count| MetricSample {
+                        labels: cpu_label(c),
+                        value: count as f64,
+                    })
+                })
+                .collect(),
+        });
+
+        // Every CPU in a package reports the same package counter, so it is
+        // exported once per physical package.
+        let mut packages: BTreeMap<String, u64> = BTreeMap::new();
+        for cpu in &cpus {
+            if let Some(count) = cpu.package_throttle_count {
+                let package = cpu.package_id.clone().unwrap_or_else(|| cpu.cpu_id.clone());
+                packages.entry(package).or_insert(count);
+            }
+        }
+        metrics.push(Metric {
+            name: "sysmetrics_cpu_package_throttles_total".to_string(),
+            help: "Number of times the CPU package was throttled due to high temperature."
+                .to_string(),
+            metric_type: MetricType::Counter,
+            samples: packages
+                .into_iter()
+                .map(|(package, count)| MetricSample {
+                    labels: vec![("package".to_string(), package)],
+                    value: count as f64,
+                })
+                .collect(),
+        });
+
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const CPU_FIXTURE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/sys/devices/system/cpu"
+    );
+
+    #[test]
+    fn test_read_cpu_freq() {
+        let cpu = read_cpu_freq(&Path::new(CPU_FIXTURE_DIR).join("cpu0"), "0");
+        assert_eq!(cpu.cpu_id, "0");
+        assert_eq!(
+            cpu.frequencies_khz,
+            vec![
+                Some(2800000),
+                Some(800000),
+                Some(4500000),
+                Some(2799998),
+                Some(800000),
+                Some(4500000),
+            ]
+        );
+        assert_eq!(cpu.governor.as_deref(), Some("powersave"));
+        assert_eq!(cpu.driver.as_deref(), Some("intel_pstate"));
+        assert_eq!(cpu.core_throttle_count, Some(12));
+        assert_eq!(cpu.package_throttle_count, Some(34));
+        assert_eq!(cpu.package_id.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn test_cpufreq_collector_frequencies() {
+        let metrics = CpuFreqCollector
+            .collect_from_dir(Path::new(CPU_FIXTURE_DIR))
+            .unwrap();
+
+        // cpufreq/ and cpuidle/ siblings are not CPUs; cpu2 has no cpufreq.
+        let cur = find_metric(&metrics, "sysmetrics_cpu_scaling_frequency_hertz");
+        assert_eq!(cur.metric_type, MetricType::Gauge);
+        assert_eq!(cur.samples.len(), 2);
+        assert_eq!(
+            cur.samples[0].labels,
+            vec![("cpu".to_string(), "0".to_string())]
+        );
+        assert_eq!(cur.samples[0].value, 2.8e9);
+
+        // cpuinfo_cur_freq is missing for cpu1.
+        let hw_cur = find_metric(&metrics, "sysmetrics_cpu_frequency_hertz");
+        assert_eq!(hw_cur.samples.len(), 1);
+
+        let max = find_metric(&metrics, "sysmetrics_cpu_frequency_max_hertz");
+        assert_eq!(max.samples[1].value, 4.5e9);
+    }
+
+    #[test]
+    fn test_cpufreq_collector_scaling_info() {
+        let metrics = CpuFreqCollector
+            .collect_from_dir(Path::new(CPU_FIXTURE_DIR))
+            .unwrap();
+        let info = find_metric(&metrics, "sysmetrics_cpu_scaling_info");
+        assert_eq!(info.samples.len(), 2);
+        assert_eq!(
+            info.samples[1].labels,
+            vec![
+                ("cpu".to_string(), "1".to_string()),
+                ("governor".to_string(), "performance".to_string()),
+                ("driver".to_string(), "intel_pstate".to_string()),
+            ]
+        );
+        assert_eq!(info.samples[1].value, 1.0);
+    }
+
+    #[test]
+    fn test_cpufreq_collector_throttles() {
+        let metrics = CpuFreqCollector
+            .collect_from_dir(Path::new(CPU_FIXTURE_DIR))
+            .unwrap();
+
+        let core = find_metric(&metrics, "sysmetrics_cpu_core_throttles_total");
+        assert_eq!(core.metric_type, MetricType::Counter);
+        assert_eq!(core.samples.len(), 3);
+        assert_eq!(core.samples[1].value, 5.0);
+
+        // cpu0 and cpu1 share package 0; cpu2 is on package 1.
+        let package = find_metric(&metrics, "sysmetrics_cpu_package_throttles_total");
+        assert_eq!(package.samples.len(), 2);
+        assert_eq!(
+            package.samples[0].labels,
+            vec![("package".to_string(), "0".to_string())]
+        );
+        assert_eq!(package.samples[0].value, 34.0);
+        assert_eq!(package.samples[1].value, 7.0);
+    }
+
+    #[test]
+    fn test_cpufreq_collector_missing_root() {
+        assert_missing_root_not_supported(|root| CpuFreqCollector.collect_from_dir(root));
+    }
+}
@@ -1,14 +1,24 @@
-use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::collector::{
+    read_sysfs_string, read_sysfs_value, Collector, Metric, MetricSample, MetricType,
+};
 use crate::error::CollectorError;
 use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 const PROC_DISKSTATS_PATH: &str = "/proc/diskstats";
+const SYS_BLOCK_PATH: &str = "/sys/block";
+const DEV_DISK_BY_ID_PATH: &str = "/dev/disk/by-id";
 const SECTOR_SIZE: f64 = 512.0;
 
 /// Parsed disk statistics for a single device.
+///
+/// The discard fields were added in kernel 4.18 and the flush fields in
+/// 5.5; they are `None` on older kernels.
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct DiskStats {
     pub device: String,
     pub reads_completed: u64,
@@ -22,6 +32,12 @@ pub struct DiskStats {
     pub ios_in_progress: u64,
     pub time_doing_ios_ms: u64,
     pub weighted_time_ms: u64,
+    pub discards_completed: Option<u64>,
+    pub discards_merged: Option<u64>,
+    pub sectors_discarded: Option<u64>,
+    pub time_discarding_ms: Option<u64>,
+    pub flushes_completed: Option<u64>,
+    pub time_flushing_ms: Option<u64>,
 }
 
 /// Parse /proc/diskstats content into a list of disk statistics.
@@ -47,6 +63,15 @@ pub fn parse_diskstats(content: &str) -> Result<Vec<DiskStats>, CollectorError>
                     raw: parts[idx].to_string(),
                 })
         };
+        // Fields that only exist on newer kernels.
+        let parse_optional =
+            |idx: usize, field: &str, dev: &str| -> Result<Option<u64>, CollectorError> {
+                if idx < parts.len() {
+                    parse_field(idx, field, dev).map(Some)
+                } else {
+                    Ok(None)
+                }
+            };
 
         stats.push(DiskStats {
             reads_completed: parse_field(3, "reads_completed", &device)?,
@@ -60,22 +85,277 @@ pub fn parse_diskstats(content: &str) -> Result<Vec<DiskStats>, CollectorError>
             ios_in_progress: parse_field(11, "ios_in_progress", &device)?,
             time_doing_ios_ms: parse_field(12, "time_doing_ios_ms", &device)?,
             weighted_time_ms: parse_field(13, "weighted_time_ms", &device)?,
+            discards_completed: parse_optional(14, "discards_completed", &device)?,
+            discards_merged: parse_optional(15, "discards_merged", &device)?,
+            sectors_discarded: parse_optional(16, "sectors_discarded", &device)?,
+            time_discarding_ms: parse_optional(17, "time_discarding_ms", &device)?,
+            flushes_completed: parse_optional(18, "flushes_completed", &device)?,
+            time_flushing_ms: parse_optional(19, "time_flushing_ms", &device)?,
             device,
         });
     }
     Ok(stats)
 }
 
+/// Device-mapper identity of a `dm-N` device (LVM, LUKS, multipath).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceMapperInfo {
+    pub name: String,
+    pub uuid: String,
+}
+
+/// Stable identity of a block device, which survives kernel renames of
+/// `sda` or `nvme0n1` across reboots. Missing strings are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskIdentity {
+    pub model: String,
+    pub serial: String,
+    pub wwid: String,
+    /// Preferred /dev/disk/by-id name pointing at the device.
+    pub id: String,
+    pub rotational: Option<bool>,
+    pub logical_block_size: Option<u64>,
+    /// Size in bytes; sysfs reports it in 512-byte sectors.
+    pub size_bytes: Option<u64>,
+}
+
+/// Block device relationships read from /sys/block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockTopology {
+    /// Kernel name (`dm-3`) to device-mapper name and uuid.
+    pub device_mapper: HashMap<String, DeviceMapperInfo>,
+    /// Devices each device is built on, from its `slaves/` directory,
+    /// e.g. `dm-0` -> [`sda2`].
+    pub parents: BTreeMap<String, Vec<String>>,
+    /// Identity of every device listed in /sys/block.
+    pub identity: HashMap<String, DiskIdentity>,
+}
+
+/// Map kernel device names to the /dev/disk/by-id symlinks resolving to
+/// them, preferring `wwn-` names since those come from the hardware.
+pub fn read_disk_by_id(by_id_dir: &Path) -> HashMap<String, String> {
+    let mut links: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(by_id_dir) else {
+        return HashMap::new();
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let (Ok(name), Ok(target)) = (entry.file_name().into_string(), fs::read_link(entry.path()))
+        else {
+            continue;
+        };
+        if let Some(device) = target.file_name().and_then(|f| f.to_str()) {
+            links.entry(device.to_string()).or_default().push(name);
+        }
+    }
+    links
+        .into_iter()
+        .filter_map(|(device, mut names)| {
+            names.sort();
+            let preferred = names
+                .iter()
+                .find(|n| n.starts_with("wwn-"))
+                .or(names.first())?
+                .clone();
+            Some((device, preferred))
+        })
+        .collect()
+}
+
+/// Read `dm/name`, `dm/uuid`, `slaves/` and the identity attributes of
+/// every device under `sys_block`. Returns an empty topology if sysfs is
+/// unavailable.
+pub fn read_block_topology(sys_block: &Path, by_id_dir: &Path) -> BlockTopology {
+    let mut topology = BlockTopology::default();
+    let Ok(entries) = fs::read_dir(sys_block) else {
+        return topology;
+    };
+    let by_id = read_disk_by_id(by_id_dir);
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(device) = entry.file_name().into_string() else {
+            continue;
+        };
+        let dir = entry.path();
+        let string = |file: &str| read_sysfs_string(&dir.join(file)).unwrap_or_default();
+        topology.identity.insert(
+            device.clone(),
+            DiskIdentity {
+                model: string("device/model"),
+                serial: string("device/serial"),
+                // NVMe namespaces have `wwid` on the block device itself.
+                wwid: read_sysfs_string(&dir.join("device/wwid"))
+                    .or_else(|| read_sysfs_string(&dir.join("wwid")))
+                    .unwrap_or_default(),
+                id: by_id.get(&device).cloned().unwrap_or_default(),
+                rotational: read_sysfs_value::<u8>(&dir.join("queue/rotational")).map(|v| v == 1),
+                logical_block_size: read_sysfs_value(&dir.join("queue/logical_block_size")),
+                size_bytes: read_sysfs_value::<u64>(&dir.join("size")).map(|v| v * 512),
+            },
+        );
+        if let Some(name) = read_sysfs_string(&dir.join("dm/name")) {
+            let uuid = read_sysfs_string(&dir.join("dm/uuid")).unwrap_or_default();
+            topology
+                .device_mapper
+                .insert(device.clone(), DeviceMapperInfo { name, uuid });
+        }
+        if let Ok(slaves) = fs::read_dir(dir.join("slaves")) {
+            let mut parents: Vec<String> = slaves
+                .filter_map(Result::ok)
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect();
+            parents.sort();
+            if !parents.is_empty() {
+                topology.parents.insert(device, parents);
+            }
+        }
+    }
+    topology
+}
+
+/// iostat-style rates of a device over the interval between two
+/// /proc/diskstats snapshots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivedDiskStats {
+    pub reads_per_second: f64,
+    pub writes_per_second: f64,
+    /// Average time a read spent queued and serviced (iostat `r_await`).
+    pub read_await_seconds: f64,
+    pub write_await_seconds: f64,
+    /// Share of the interval the device was busy (iostat `%util`).
+    pub utilization_percent: f64,
+    /// Average number of requests in flight (iostat `aqu-sz`).
+    pub average_queue_size: f64,
+}
+
+/// Compute rates between two snapshots of the same device taken `elapsed`
+/// apart. Returns `None` if `elapsed` is zero or any counter went
+/// backwards, which happens when a 32-bit counter wraps or the device was
+/// re-created; that interval is discarded rather than reported as a spike.
+pub fn compute_derived(
+    previous: &DiskStats,
+    current: &DiskStats,
+    elapsed: Duration,
+) -> Option<DerivedDiskStats> {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        return None;
+    }
+    let delta = |field: fn(&DiskStats) -> u64| field(current).checked_sub(field(previous));
+    let reads = delta(|s| s.reads_completed)?;
+    let writes = delta(|s| s.writes_completed)?;
+    let read_ms = delta(|s| s.time_reading_ms)?;
+    let write_ms = delta(|s| s.time_writing_ms)?;
+    let io_ms = delta(|s| s.time_doing_ios_ms)?;
+    let weighted_ms = delta(|s| s.weighted_time_ms)?;
+
+    let await_seconds = |ms: u64, ios: u64| {
+        if ios == 0 {
+            0.0
+        } else {
+            ms as f64 / 1000.0 / ios as f64
+        }
+    };
+    Some(DerivedDiskStats {
+        reads_per_second: reads as f64 / seconds,
+        writes_per_second: writes as f64 / seconds,
+        read_await_seconds: await_seconds(read_ms, reads),
+        write_await_seconds: await_seconds(write_ms, writes),
+        // io_ticks can run slightly ahead of wall-clock time.
+        utilization_percent: (io_ms as f64 / 1000.0 / seconds * 100.0).min(100.0),
+        average_queue_size: weighted_ms as f64 / 1000.0 / seconds,
+    })
+}
+
+/// Last snapshot of a device and the rates computed when it was taken.
+struct DiskSnapshot {
+    stats: DiskStats,
+    taken_at: Instant,
+    derived: Option<DerivedDiskStats>,
+}
+
 pub struct DiskCollector {
     exclude_pattern: Regex,
+    parent_info: bool,
+    identity_labels: bool,
+    derived_min_interval: Option<Duration>,
+    snapshots: Mutex<HashMap<String, DiskSnapshot>>,
 }
 
 impl DiskCollector {
-    pub fn new(exclude_pattern: &str) -> Result<Self, regex::Error> {
+    /// With `parent_info`, `sysmetrics_disk_parent_info` maps each device
+    /// to the devices it is built on. With `identity_labels`, the
+    /// `model`, `serial`, `wwid` and `id` labels of `sysmetrics_disk_info`
+    /// are also added to every /proc/diskstats series.
+    ///
+    /// With `derived_min_interval`, the collector keeps the previous
+    /// snapshot of every device and exports iostat-style rates. Scrapes
+    /// closer together than the interval repeat the last computed rates.
+    pub fn new(
+        exclude_pattern: &str,
+        parent_info: bool,
+        identity_labels: bool,
+        derived_min_interval: Option<Duration>,
+    ) -> Result<Self, regex::Error> {
         Ok(Self {
             exclude_pattern: Regex::new(exclude_pattern)?,
+            parent_info,
+            identity_labels,
+            derived_min_interval,
+            snapshots: Mutex::new(HashMap::new()),
         })
     }
+
+    /// Update the per-device snapshots with `stats` taken at `now` and
+    /// return the current rates of each device that has them. Devices no
+    /// longer in `stats` are forgotten.
+    fn update_derived(
+        &self,
+        stats: &[&DiskStats],
+        now: Instant,
+        min_interval: Duration,
+    ) -> Vec<(String, DerivedDiskStats)> {
+        let mut snapshots = self
+            .snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        snapshots.retain(|device, _| stats.iter().any(|s| &s.device == device));
+
+        let mut derived = Vec::new();
+        for current in stats {
+            match snapshots.get_mut(&current.device) {
+                Some(snapshot) => {
+                    let elapsed = now.saturating_duration_since(snapshot.taken_at);
+                    if elapsed >= min_interval {
+                        snapshot.derived = compute_derived(&snapshot.stats, current, elapsed);
+                        snapshot.stats = (*current).clone();
+                        snapshot.taken_at = now;
+                    }
+                    if let Some(d) = snapshot.derived {
+                        derived.push((current.device.clone(), d));
+                    }
+                }
+                None => {
+                    snapshots.insert(
+                        current.device.clone(),
+                        DiskSnapshot {
+                            stats: (*current).clone(),
+                            taken_at: now,
+                            derived: None,
+                        },
+                    );
+                }
+            }
+        }
+        derived
+    }
+}
+
+fn identity_labels(identity: &DiskIdentity) -> Vec<(String, String)> {
+    vec![
+        ("model".to_string(), identity.model.clone()),
+        ("serial".to_string(), identity.serial.clone()),
+        ("wwid".to_string(), identity.wwid.clone()),
+        ("id".to_string(), identity.id.clone()),
+    ]
 }
 
 impl Collector for DiskCollector {
@@ -89,60 +369,161 @@ impl Collector for DiskCollector {
                 path: PROC_DISKSTATS_PATH.to_string(),
                 source: e,
             })?;
-        self.collect_from_string(&content)
+        let topology =
+            read_block_topology(Path::new(SYS_BLOCK_PATH), Path::new(DEV_DISK_BY_ID_PATH));
+        self.collect_from_string(&content, &topology)
     }
 }
 
 impl DiskCollector {
-    pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
+    pub fn collect_from_string(
+        &self,
+        content: &str,
+        topology: &BlockTopology,
+    ) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_string_at(content, topology, Instant::now())
+    }
+
+    /// Like `collect_from_string`, with `now` as the snapshot time for
+    /// derived stats.
+    pub fn collect_from_string_at(
+        &self,
+        content: &str,
+        topology: &BlockTopology,
+        now: Instant,
+    ) -> Result<Vec<Metric>, CollectorError> {
         let all_stats = parse_diskstats(content)?;
         let stats: Vec<&DiskStats> = all_stats
             .iter()
             .filter(|s| !self.exclude_pattern.is_match(&s.device))
             .collect();
 
+        // Device-mapper devices also get their human-readable name.
+        let device_labels = |device: &str| {
+            let mut labels = vec![("device".to_string(), device.to_string())];
+            if let Some(dm) = topology.device_mapper.get(device) {
+                labels.push(("name".to_string(), dm.name.clone()));
+            }
+            labels
+        };
+        let series_labels = |device: &str| {
+            let mut labels = device_labels(device);
+            if self.identity_labels {
+                if let Some(identity) = topology.identity.get(device) {
+                    labels.extend(identity_labels(identity));
+                }
+            }
+            labels
+        };
+
         type MetricDef = (
             &'static str,
             &'static str,
             MetricType,
-            Box<dyn Fn(&DiskStats) -> f64>,
+            Box<dyn Fn(&DiskStats) -> Option<f64>>,
         );
         let metric_defs: Vec<MetricDef> = vec![
             (
                 "sysmetrics_disk_reads_completed_total",
                 "Total number of reads completed.",
                 MetricType::Counter,
-                Box::new(|s: &DiskStats| s.reads_completed as f64),
+                Box::new(|s: &DiskStats| Some(s.reads_completed as f64)),
             ),
             (
                 "sysmetrics_disk_writes_completed_total",
                 "Total number of writes completed.",
                 MetricType::Counter,
-                Box::new(|s: &DiskStats| s.writes_completed as f64),
+                Box::new(|s: &DiskStats| Some(s.writes_completed as f64)),
             ),
             (
                 "sysmetrics_disk_read_bytes_total",
                 "Total bytes read from disk.",
                 MetricType::Counter,
-                Box::new(|s: &DiskStats| s.sectors_read as f64 * SECTOR_SIZE),
+                Box::new(|s: &DiskStats| Some(s.sectors_read as f64 * SECTOR_SIZE)),
             ),
             (
                 "sysmetrics_disk_written_bytes_total",
                 "Total bytes written to disk.",
                 MetricType::Counter,
-                Box::new(|s: &DiskStats| s.sectors_written as f64 * SECTOR_SIZE),
+                Box::new(|s: &DiskStats| Some(s.sectors_written as f64 * SECTOR_SIZE)),
             ),
             (
                 "sysmetrics_disk_io_time_seconds_total",
                 "Total time spent doing I/Os in seconds.",
                 MetricType::Counter,
-                Box::new(|s: &DiskStats| s.time_doing_ios_ms as f64 / 1000.0),
+                Box::new(|s: &DiskStats| Some(s.time_doing_ios_ms as f64 / 1000.0)),
             ),
             (
                 "sysmetrics_disk_io_in_progress",
                 "Number of I/Os currently in progress.",
                 MetricType::Gauge,
-                Box::new(|s: &DiskStats| s.ios_in_progress as f64),
+                Box::new(|s: &DiskStats| Some(s.ios_in_progress as f64)),
+            ),
+            (
+                "sysmetrics_disk_reads_merged_total",
+                "Total number of adjacent reads merged.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| Some(s.reads_merged as f64)),
+            ),
+            (
+                "sysmetrics_disk_writes_merged_total",
+                "Total number of adjacent writes merged.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| Some(s.writes_merged as f64)),
+            ),
+            (
+                "sysmetrics_disk_read_time_seconds_total",
+                "Total time spent on reads in seconds.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| Some(s.time_reading_ms as f64 / 1000.0)),
+            ),
+            (
+                "sysmetrics_disk_write_time_seconds_total",
+                "Total time spent on writes in seconds.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| Some(s.time_writing_ms as f64 / 1000.0)),
+            ),
+            (
+                "sysmetrics_disk_io_time_weighted_seconds_total",
+                "Total time spent doing I/Os weighted by the number of I/Os in progress, in seconds.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| Some(s.weighted_time_ms as f64 / 1000.0)),
+            ),
+            (
+                "sysmetrics_disk_discards_completed_total",
+                "Total number of discards completed.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.discards_completed.map(|v| v as f64)),
+            ),
+            (
+                "sysmetrics_disk_discards_merged_total",
+                "Total number of adjacent discards merged.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.discards_merged.map(|v| v as f64)),
+            ),
+            (
+                "sysmetrics_disk_discarded_bytes_total",
+                "Total bytes discarded.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.sectors_discarded.map(|v| v as f64 * SECTOR_SIZE)),
+            ),
+            (
+                "sysmetrics_disk_discard_time_seconds_total",
+                "Total time spent on discards in seconds.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.time_discarding_ms.map(|v| v as f64 / 1000.0)),
+            ),
+            (
+                "sysmetrics_disk_flush_requests_total",
+                "Total number of flush requests completed.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.flushes_completed.map(|v| v as f64)),
+            ),
+            (
+                "sysmetrics_disk_flush_requests_time_seconds_total",
+                "Total time spent on flush requests in seconds.",
+                MetricType::Counter,
+                Box::new(|s: &DiskStats| s.time_flushing_ms.map(|v| v as f64 / 1000.0)),
             ),
         ];
 
@@ -150,9 +531,11 @@ impl DiskCollector {
         for (name, help, metric_type, value_fn) in &metric_defs {
             let samples = stats
                 .iter()
-                .map(|s| MetricSample {
-                    labels: vec![("device".to_string(), s.device.clone())],
-                    value: value_fn(s),
+                .filter_map(|s| {
+                    value_fn(s).map(|value| MetricSample {
+                        labels: series_labels(&s.device),
+                        value,
+                    })
                 })
                 .collect();
             metrics.push(Metric {
@@ -163,6 +546,151 @@ impl DiskCollector {
             });
         }
 
+        metrics.push(Metric {
+            name: "sysmetrics_disk_device_mapper_info".to_string(),
+            help: "Device-mapper name and uuid of dm devices.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: stats
+                .iter()
+                .filter_map(|s| {
+                    let dm = topology.device_mapper.get(&s.device)?;
+                    Some(MetricSample {
+                        labels: vec![
+                            ("device".to_string(), s.device.clone()),
+                            ("name".to_string(), dm.name.clone()),
+                            ("uuid".to_string(), dm.uuid.clone()),
+                        ],
+                        value: 1.0,
+                    })
+                })
+                .collect(),
+        });
+
+        let identities: Vec<(&str, &DiskIdentity)> = stats
+            .iter()
+            .filter_map(|s| Some((s.device.as_str(), topology.identity.get(&s.device)?)))
+            .collect();
+        metrics.push(Metric {
+            name: "sysmetrics_disk_info".to_string(),
+            help: "Hardware identity of block devices from sysfs and /dev/disk/by-id.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: identities
+                .iter()
+                .map(|(device, identity)| {
+                    let mut labels = device_labels(device);
+                    labels.extend(identity_labels(identity));
+                    MetricSample { labels, value: 1.0 }
+                })
+                .collect(),
+        });
+        type IdentityDef = (&'static str, &'static str, fn(&DiskIdentity) -> Option<f64>);
+        let identity_defs: [IdentityDef; 3] = [
+            (
+                "sysmetrics_disk_rotational",
+                "Whether the device is rotational (1) or solid-state (0).",
+                |i| i.rotational.map(|r| if r { 1.0 } else { 0.0 }),
+            ),
+            (
+                "sysmetrics_disk_logical_block_size_bytes",
+                "Logical block size of the device in bytes.",
+                |i| i.logical_block_size.map(|v| v as f64),
+            ),
+            (
+                "sysmetrics_disk_size_bytes",
+                "Size of the device in bytes.",
+                |i| i.size_bytes.map(|v| v as f64),
+            ),
+        ];
+        for (name, help, value_fn) in identity_defs {
+            metrics.push(Metric {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric_type: MetricType::Gauge,
+                samples: identities
+                    .iter()
+                    .filter_map(|(device, identity)| {
+                        value_fn(identity).map(|value| MetricSample {
+                            labels: series_labels(device),
+                            value,
+                        })
+                    })
+                    .collect(),
+            });
+        }
+
+        if let Some(min_interval) = self.derived_min_interval {
+            let derived = self.update_derived(&stats, now, min_interval);
+            type DerivedDef = (&'static str, &'static str, fn(&DerivedDiskStats) -> f64);
+            let derived_defs: [DerivedDef; 6] = [
+                (
+                    "sysmetrics_disk_reads_per_second",
+                    "Reads completed per second over the last interval.",
+                    |d| d.reads_per_second,
+                ),
+                (
+                    "sysmetrics_disk_writes_per_second",
+                    "Writes completed per second over the last interval.",
+                    |d| d.writes_per_second,
+                ),
+                (
+                    "sysmetrics_disk_read_await_seconds",
+                    "Average time per read over the last interval, including queueing.",
+                    |d| d.read_await_seconds,
+                ),
+                (
+                    "sysmetrics_disk_write_await_seconds",
+                    "Average time per write over the last interval, including queueing.",
+                    |d| d.write_await_seconds,
+                ),
+                (
+                    "sysmetrics_disk_utilization_percent",
+                    "Percentage of the last interval the device was busy.",
+                    |d| d.utilization_percent,
+                ),
+                (
+                    "sysmetrics_disk_average_queue_size",
+                    "Average number of requests in flight over the last interval.",
+                    |d| d.average_queue_size,
+                ),
+            ];
+            for (name, help, value_fn) in derived_defs {
+                metrics.push(Metric {
+                    name: name.to_string(),
+                    help: help.to_string(),
+                    metric_type: MetricType::Gauge,
+                    samples: derived
+                        .iter()
+                        .map(|(device, d)| MetricSample {
+                            labels: series_labels(device),
+                            value: value_fn(d),
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        if self.parent_info {
+            metrics.push(Metric {
+                name: "sysmetrics_disk_parent_info".to_string(),
+                help: "Devices a block device is built on, from /sys/block/<dev>/slaves."
+                    .to_string(),
+                metric_type: MetricType::Gauge,
+                samples: stats
+                    .iter()
+                    .filter_map(|s| topology.parents.get(&s.device).map(|p| (s, p)))
+                    .flat_map(|(s, parents)| {
+                        parents.iter().map(|parent| MetricSample {
+                            labels: vec![
+                                ("device".to_string(), s.device.clone()),
+                                ("parent".to_string(), parent.clone()),
+                            ],
+                            value: 1.0,
+                        })
+                    })
+                    .collect(),
+            });
+        }
+
         Ok(metrics)
     }
 }
@@ -170,6 +698,19 @@ impl DiskCollector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collector::find_metric;
+
+    const SYS_BLOCK_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/block");
+    const BY_ID_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dev/disk/by-id");
+
+    fn fixture_topology() -> BlockTopology {
+        read_block_topology(
+            Path::new(SYS_BLOCK_FIXTURE_DIR),
+            Path::new(BY_ID_FIXTURE_DIR),
+        )
+    }
 
     const DISKSTATS_FIXTURE: &str = "\
    8       0 sda 12345 100 98765 4567 54321 200 87654 3456 5 6789 12345
@@ -177,12 +718,13 @@ mod tests {
    7       0 loop0 100 0 200 10 0 0 0 0 0 10 10
    1       0 ram0 0 0 0 0 0 0 0 0 0 0 0
  253       0 dm-0 5000 0 40000 2000 3000 0 24000 1000 2 3000 5000
+ 253       1 dm-1 4000 0 30000 1500 2000 0 16000 800 0 2500 4000
 ";
 
     #[test]
     fn test_parse_diskstats() {
         let stats = parse_diskstats(DISKSTATS_FIXTURE).unwrap();
-        assert_eq!(stats.len(), 5);
+        assert_eq!(stats.len(), 6);
         assert_eq!(stats[0].device, "sda");
         assert_eq!(stats[0].reads_completed, 12345);
         assert_eq!(stats[0].sectors_read, 98765);
@@ -191,6 +733,56 @@ mod tests {
         assert_eq!(stats[0].ios_in_progress, 5);
     }
 
+    /// Kernel 4.18 added four discard fields.
+    const DISKSTATS_4_18_FIXTURE: &str = "\
+   8       0 sda 12345 100 98765 4567 54321 200 87654 3456 0 6789 12345 42 3 8192 17
+";
+
+    /// Kernel 5.5 added two flush fields.
+    const DISKSTATS_5_5_FIXTURE: &str = "\
+ 259       0 nvme0n1 12345 100 98765 4567 54321 200 87654 3456 0 6789 12345 42 3 8192 17 900 250
+ 259       1 nvme0n1p1 1234 10 9876 456 5432 20 8765 345 0 678 1234 4 0 800 2 0 0
+";
+
+    #[test]
+    fn test_parse_diskstats_old_kernel() {
+        let stats = parse_diskstats(DISKSTATS_FIXTURE).unwrap();
+        assert_eq!(stats[0].reads_merged, 100);
+        assert_eq!(stats[0].time_reading_ms, 4567);
+        assert_eq!(stats[0].writes_merged, 200);
+        assert_eq!(stats[0].time_writing_ms, 3456);
+        assert_eq!(stats[0].weighted_time_ms, 12345);
+        assert_eq!(stats[0].discards_completed, None);
+        assert_eq!(stats[0].flushes_completed, None);
+    }
+
+    #[test]
+    fn test_parse_diskstats_discard_fields() {
+        let stats = parse_diskstats(DISKSTATS_4_18_FIXTURE).unwrap();
+        assert_eq!(stats[0].discards_completed, Some(42));
+        assert_eq!(stats[0].discards_merged, Some(3));
+        assert_eq!(stats[0].sectors_discarded, Some(8192));
+        assert_eq!(stats[0].time_discarding_ms, Some(17));
+        assert_eq!(stats[0].flushes_completed, None);
+        assert_eq!(stats[0].time_flushing_ms, None);
+    }
+
+    #[test]
+    fn test_parse_diskstats_flush_fields() {
+        let stats = parse_diskstats(DISKSTATS_5_5_FIXTURE).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].sectors_discarded, Some(8192));
+        assert_eq!(stats[0].flushes_completed, Some(900));
+        assert_eq!(stats[0].time_flushing_ms, Some(250));
+        assert_eq!(stats[1].flushes_completed, Some(0));
+    }
+
+    #[test]
+    fn test_parse_diskstats_malformed_optional_field() {
+        let input = "   8       0 sda 1 2 3 4 5 6 7 8 9 10 11 12 x 14 15\n";
+        assert!(parse_diskstats(input).is_err());
+    }
+
     #[test]
     fn test_parse_diskstats_empty() {
         let stats = parse_diskstats("").unwrap();
@@ -199,8 +791,10 @@ mod tests {
 
     #[test]
     fn test_disk_collector_filters_virtual_devices() {
-        let collector = DiskCollector::new("^(loop|ram|dm-)").unwrap();
-        let metrics = collector.collect_from_string(DISKSTATS_FIXTURE).unwrap();
+        let collector = DiskCollector::new("^(loop|ram|dm-)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &BlockTopology::default())
+            .unwrap();
         // Should only have sda and sda1 (loop0, ram0, dm-0 filtered)
         let reads_metric = &metrics[0];
         assert_eq!(reads_metric.samples.len(), 2);
@@ -210,8 +804,10 @@ mod tests {
 
     #[test]
     fn test_disk_collector_metric_values() {
-        let collector = DiskCollector::new("^(loop|ram|dm-)").unwrap();
-        let metrics = collector.collect_from_string(DISKSTATS_FIXTURE).unwrap();
+        let collector = DiskCollector::new("^(loop|ram|dm-)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &BlockTopology::default())
+            .unwrap();
 
         // reads_completed_total for sda = 12345
         assert_eq!(metrics[0].samples[0].value, 12345.0);
@@ -224,8 +820,392 @@ mod tests {
     #[test]
     fn test_disk_collector_device_with_hyphen() {
         let input = "   8       0 nvme0n1 1000 0 2000 100 500 0 1000 50 1 150 200\n";
-        let collector = DiskCollector::new("^(loop|ram|dm-)").unwrap();
-        let metrics = collector.collect_from_string(input).unwrap();
+        let collector = DiskCollector::new("^(loop|ram|dm-)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(input, &BlockTopology::default())
+            .unwrap();
         assert_eq!(metrics[0].samples[0].labels[0].1, "nvme0n1");
     }
+
+    #[test]
+    fn test_disk_collector_time_and_merge_metrics() {
+        let collector = DiskCollector::new("^(loop|ram|dm-)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &BlockTopology::default())
+            .unwrap();
+
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_disk_reads_merged_total").samples[0].value,
+            100.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_disk_writes_merged_total").samples[0].value,
+            200.0
+        );
+        assert!(
+            (find_metric(&metrics, "sysmetrics_disk_read_time_seconds_total").samples[0].value
+                - 4.567)
+                .abs()
+                < 0.001
+        );
+        assert!(
+            (find_metric(&metrics, "sysmetrics_disk_write_time_seconds_total").samples[0].value
+                - 3.456)
+                .abs()
+                < 0.001
+        );
+        assert!(
+            (find_metric(&metrics, "sysmetrics_disk_io_time_weighted_seconds_total").samples[0]
+                .value
+                - 12.345)
+                .abs()
+                < 0.001
+        );
+        // Old kernel: no discard or flush samples.
+        assert!(
+            find_metric(&metrics, "sysmetrics_disk_discards_completed_total")
+                .samples
+                .is_empty()
+        );
+        assert!(
+            find_metric(&metrics, "sysmetrics_disk_flush_requests_total")
+                .samples
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_disk_collector_discard_and_flush_metrics() {
+        let collector = DiskCollector::new("^(loop|ram|dm-)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_5_5_FIXTURE, &BlockTopology::default())
+            .unwrap();
+
+        let discarded = find_metric(&metrics, "sysmetrics_disk_discarded_bytes_total");
+        assert_eq!(discarded.samples.len(), 2);
+        assert_eq!(discarded.samples[0].value, 8192.0 * 512.0);
+        assert!(
+            (find_metric(&metrics, "sysmetrics_disk_discard_time_seconds_total").samples[0].value
+                - 0.017)
+                .abs()
+                < 0.001
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_disk_flush_requests_total").samples[0].value,
+            900.0
+        );
+        assert!(
+            (find_metric(
+                &metrics,
+                "sysmetrics_disk_flush_requests_time_seconds_total"
+            )
+            .samples[0]
+                .value
+                - 0.25)
+                .abs()
+                < 0.001
+        );
+    }
+
+    #[test]
+    fn test_read_block_topology() {
+        let topology = fixture_topology();
+        assert_eq!(topology.device_mapper.len(), 2);
+        assert_eq!(topology.device_mapper["dm-0"].name, "vg0-root");
+        assert!(topology.device_mapper["dm-1"]
+            .uuid
+            .starts_with("CRYPT-LUKS2-"));
+        assert_eq!(topology.parents["dm-0"], vec!["sda2".to_string()]);
+        assert_eq!(topology.parents["dm-1"], vec!["dm-0".to_string()]);
+
+        let missing = read_block_topology(
+            Path::new("/nonexistent/block"),
+            Path::new("/nonexistent/by-id"),
+        );
+        assert_eq!(missing, BlockTopology::default());
+    }
+
+    #[test]
+    fn test_disk_collector_device_mapper_names() {
+        let topology = fixture_topology();
+        let collector = DiskCollector::new("^(loop|ram)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &topology)
+            .unwrap();
+
+        let reads = &metrics[0];
+        assert_eq!(reads.samples.len(), 4);
+        // Plain disks keep only the device label.
+        assert_eq!(
+            reads.samples[0].labels,
+            vec![("device".to_string(), "sda".to_string())]
+        );
+        assert_eq!(
+            reads.samples[2].labels,
+            vec![
+                ("device".to_string(), "dm-0".to_string()),
+                ("name".to_string(), "vg0-root".to_string()),
+            ]
+        );
+        assert_eq!(reads.samples[2].value, 5000.0);
+
+        let info = metrics
+            .iter()
+            .find(|m| m.name == "sysmetrics_disk_device_mapper_info")
+            .unwrap();
+        assert_eq!(info.samples.len(), 2);
+        assert_eq!(info.samples[1].labels[1].1, "luks-3f1c2b7a");
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name == "sysmetrics_disk_parent_info"));
+    }
+
+    #[test]
+    fn test_disk_collector_parent_info() {
+        let topology = fixture_topology();
+        let collector = DiskCollector::new("^(loop|ram)", true, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &topology)
+            .unwrap();
+        let parents = metrics
+            .iter()
+            .find(|m| m.name == "sysmetrics_disk_parent_info")
+            .unwrap();
+        assert_eq!(parents.samples.len(), 2);
+        assert_eq!(
+            parents.samples[1].labels,
+            vec![
+                ("device".to_string(), "dm-1".to_string()),
+                ("parent".to_string(), "dm-0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_disk_identity() {
+        let topology = fixture_topology();
+        assert_eq!(
+            topology.identity["sda"],
+            DiskIdentity {
+                model: "Samsung SSD 860".to_string(),
+                serial: "S3Z9NB0K123456".to_string(),
+                wwid: "naa.5002538e40a1b2c3".to_string(),
+                id: "wwn-0x5002538e40a1b2c3".to_string(),
+                rotational: Some(false),
+                logical_block_size: Some(512),
+                size_bytes: Some(1953525168 * 512),
+            }
+        );
+        // NVMe wwid lives on the namespace, not the controller.
+        let nvme = &topology.identity["nvme0n1"];
+        assert_eq!(nvme.wwid, "eui.0025385b71b2c3d4");
+        assert_eq!(nvme.id, "nvme-Samsung_SSD_970_EVO_S4EWNX0R123456");
+        // Devices without hardware attributes have empty strings.
+        assert_eq!(topology.identity["dm-0"].model, "");
+        assert_eq!(topology.identity["dm-0"].id, "dm-name-vg0-root");
+    }
+
+    #[test]
+    fn test_disk_collector_info() {
+        let collector = DiskCollector::new("^(loop|ram)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &fixture_topology())
+            .unwrap();
+
+        let info = find_metric(&metrics, "sysmetrics_disk_info");
+        // sda1 is a partition and has no /sys/block entry.
+        assert_eq!(info.samples.len(), 3);
+        assert_eq!(
+            info.samples[0].labels,
+            vec![
+                ("device".to_string(), "sda".to_string()),
+                ("model".to_string(), "Samsung SSD 860".to_string()),
+                ("serial".to_string(), "S3Z9NB0K123456".to_string()),
+                ("wwid".to_string(), "naa.5002538e40a1b2c3".to_string()),
+                ("id".to_string(), "wwn-0x5002538e40a1b2c3".to_string()),
+            ]
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_disk_rotational").samples[0].value,
+            0.0
+        );
+        let size = find_metric(&metrics, "sysmetrics_disk_size_bytes");
+        assert_eq!(size.samples.len(), 1);
+        assert_eq!(size.samples[0].value, 1953525168.0 * 512.0);
+
+        // Without the option, diskstats series keep their plain labels.
+        assert_eq!(metrics[0].samples[0].labels.len(), 1);
+    }
+
+    #[test]
+    fn test_disk_collector_identity_labels() {
+        let collector = DiskCollector::new("^(loop|ram)", false, true, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &fixture_topology())
+            .unwrap();
+        let reads = &metrics[0];
+        assert_eq!(reads.samples[0].labels.len(), 5);
+        assert_eq!(reads.samples[0].labels[4].1, "wwn-0x5002538e40a1b2c3");
+        // Partitions are not in /sys/block and get no identity labels.
+        assert_eq!(reads.samples[1].labels.len(), 1);
+        // dm devices keep their name label ahead of the identity labels.
+        assert_eq!(reads.samples[2].labels[1].1, "vg0-root");
+        assert_eq!(reads.samples[2].labels.len(), 6);
+    }
+
+    fn derived_value(metrics: &[Metric], name: &str, device: &str) -> Option<f64> {
+        metrics
+            .iter()
+            .find(|m| m.name == name)?
+            .samples
+            .iter()
+            .find(|s| s.labels[0].1 == device)
+            .map(|s| s.value)
+    }
+
+    #[test]
+    fn test_compute_derived() {
+        let stats = parse_diskstats(
+            "\
+   8       0 sda 1000 0 8000 2000 500 0 4000 3000 0 1000 5000
+   8       0 sda 1100 0 8800 2400 700 0 5600 3800 1 1500 6200
+",
+        )
+        .unwrap();
+        let derived = compute_derived(&stats[0], &stats[1], Duration::from_secs(2)).unwrap();
+        assert_eq!(
+            derived,
+            DerivedDiskStats {
+                reads_per_second: 50.0,
+                writes_per_second: 100.0,
+                // 400 ms over 100 reads, 800 ms over 200 writes
+                read_await_seconds: 0.004,
+                write_await_seconds: 0.004,
+                // 500 ms busy in 2 s
+                utilization_percent: 25.0,
+                // 1200 ms weighted in 2 s
+                average_queue_size: 0.6,
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_derived_idle_and_wrap() {
+        let stats = parse_diskstats(
+            "\
+   8       0 sda 4294967290 0 8000 2000 500 0 4000 3000 0 1000 5000
+   8       0 sda 4 0 8000 2000 500 0 4000 3000 0 1000 5000
+",
+        )
+        .unwrap();
+        let idle = compute_derived(&stats[0], &stats[0], Duration::from_secs(1)).unwrap();
+        assert_eq!(idle.read_await_seconds, 0.0);
+        assert_eq!(idle.utilization_percent, 0.0);
+        // A counter going backwards discards the interval.
+        assert!(compute_derived(&stats[0], &stats[1], Duration::from_secs(1)).is_none());
+        assert!(compute_derived(&stats[0], &stats[0], Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_disk_collector_derived_stats() {
+        let collector =
+            DiskCollector::new("^(loop|ram)", false, false, Some(Duration::from_secs(5))).unwrap();
+        let topology = BlockTopology::default();
+        let start = Instant::now();
+        let first = "   8       0 sda 1000 0 8000 2000 500 0 4000 3000 0 1000 5000\n";
+        let second = "   8       0 sda 1100 0 8800 2400 700 0 5600 3800 1 11000 6200\n";
+        let third = "   8       0 sda 1200 0 9600 2800 900 0 6400 4600 0 12000 7400\n";
+
+        // The first scrape has no previous snapshot.
+        let metrics = collector
+            .collect_from_string_at(first, &topology, start)
+            .unwrap();
+        let util = metrics
+            .iter()
+            .find(|m| m.name == "sysmetrics_disk_utilization_percent")
+            .unwrap();
+        assert!(util.samples.is_empty());
+
+        let metrics = collector
+            .collect_from_string_at(second, &topology, start + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_utilization_percent", "sda"),
+            Some(100.0)
+        );
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_reads_per_second", "sda"),
+            Some(10.0)
+        );
+
+        // Within the minimum interval the last rates are repeated.
+        let metrics = collector
+            .collect_from_string_at(third, &topology, start + Duration::from_secs(12))
+            .unwrap();
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_reads_per_second", "sda"),
+            Some(10.0)
+        );
+        let metrics = collector
+            .collect_from_string_at(third, &topology, start + Duration::from_secs(20))
+            .unwrap();
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_reads_per_second", "sda"),
+            Some(10.0)
+        );
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_utilization_percent", "sda"),
+            Some(10.0)
+        );
+    }
+
+    #[test]
+    fn test_disk_collector_derived_vanished_device() {
+        let collector =
+            DiskCollector::new("^(loop|ram)", false, false, Some(Duration::ZERO)).unwrap();
+        let topology = BlockTopology::default();
+        let start = Instant::now();
+        let both = "\
+   8       0 sda 1000 0 8000 2000 500 0 4000 3000 0 1000 5000
+   8      16 sdb 1000 0 8000 2000 500 0 4000 3000 0 1000 5000
+";
+        let sda_only = "   8       0 sda 1000 0 8000 2000 500 0 4000 3000 0 1000 5000\n";
+        // sdb comes back with reset counters.
+        let sdb_reset = "\
+   8       0 sda 1000 0 8000 2000 500 0 4000 3000 0 1000 5000
+   8      16 sdb 10 0 80 20 5 0 40 30 0 10 50
+";
+        collector
+            .collect_from_string_at(both, &topology, start)
+            .unwrap();
+        collector
+            .collect_from_string_at(sda_only, &topology, start + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(collector.snapshots.lock().unwrap().len(), 1);
+
+        let metrics = collector
+            .collect_from_string_at(sdb_reset, &topology, start + Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_reads_per_second", "sda"),
+            Some(0.0)
+        );
+        // sdb starts over from a fresh snapshot instead of a negative delta.
+        assert_eq!(
+            derived_value(&metrics, "sysmetrics_disk_reads_per_second", "sdb"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_disk_collector_derived_disabled() {
+        let collector = DiskCollector::new("^(loop|ram)", false, false, None).unwrap();
+        let metrics = collector
+            .collect_from_string(DISKSTATS_FIXTURE, &BlockTopology::default())
+            .unwrap();
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name == "sysmetrics_disk_utilization_percent"));
+        assert!(collector.snapshots.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,438 @@
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use regex::Regex;
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+const PROC_MOUNTS_PATH: &str = "/proc/self/mounts";
+
+/// A single entry from the mount table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub device: String,
+    pub mountpoint: String,
+    pub fstype: String,
+}
+
+/// Space and inode usage of a mounted filesystem, as reported by statvfs(3).
+#[derive(Debug, Clone, Copy)]
+pub struct FilesystemUsage {
+    pub size_bytes: u64,
+    pub free_bytes: u64,
+    pub avail_bytes: u64,
+    pub files: u64,
+    pub files_free: u64,
+}
+
+/// Parse /proc/self/mounts content into mount entries.
+///
+/// The kernel escapes whitespace and backslashes in paths as octal sequences
+/// (e.g. `\040` for a space); these are decoded.
+pub fn parse_mounts(content: &str) -> Result<Vec<MountEntry>, CollectorError> {
+    let mut mounts = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 3 {
+            return Err(CollectorError::Parse {
+                path: PROC_MOUNTS_PATH.to_string(),
+                field: "mount line".to_string(),
+                raw: line.to_string(),
+            });
+        }
+        mounts.push(MountEntry {
+            device: unescape_mount_field(parts[0]),
+            mountpoint: unescape_mount_field(parts[1]),
+            fstype: parts[2].to_string(),
+        });
+    }
+    Ok(mounts)
+}
+
+/// Decode the `\NNN` octal escapes used in the mount table.
+fn unescape_mount_field(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            let octal = &bytes[i + 1..i + 4];
+            if octal.iter().all(|b| (b'0'..=b'7').contains(b)) {
+                let value = octal
+                    .iter()
+                    .fold(0u32, |acc, b| acc * 8 + (b - b'0') as u32);
+                if let Ok(b) = u8::try_from(value) {
+                    out.push(b);
+                    i += 4;
+                    continue;
+                }
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Call statvfs(3) on a mountpoint.
+fn statvfs(mountpoint: &str) -> io::Result<FilesystemUsage> {
+    let c_path = CString::new(mountpoint)?;
+    // SAFETY: `statvfs` is plain old data, so an all-zero value is valid.
+    let mut buf: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is a valid NUL-terminated string and `buf` is a valid
+    // out-pointer for the duration of the call.
+    let ret = unsafe { libc::statvfs(c_path.as_ptr(), &mut buf) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let frsize = buf.f_frsize as u64;
+    Ok(FilesystemUsage {
+        size_bytes: buf.f_blocks as u64 * frsize,
+        free_bytes: buf.f_bfree as u64 * frsize,
+        avail_bytes: buf.f_bavail as u64 * frsize,
+        files: buf.f_files as u64,
+        files_free: buf.f_ffree as u64,
+    })
+}
+
+pub struct FilesystemCollector {
+    mountpoint_include: Regex,
+    mountpoint_exclude: Regex,
+    fstype_include: Regex,
+    fstype_exclude: Regex,
+    mount_timeout: Duration,
+    /// Mountpoints whose statvfs call timed out and has not returned yet.
+    /// They are skipped until the blocked call completes.
+    stuck_mounts: Arc<Mutex<HashSet<String>>>,
+}
+
+impl FilesystemCollector {
+    pub fn new(
+        mountpoint_include: &str,
+        mountpoint_exclude: &str,
+        fstype_include: &str,
+        fstype_exclude: &str,
+        mount_timeout: Duration,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            mountpoint_include: Regex::new(mountpoint_include)?,
+            mountpoint_exclude: Regex::new(mountpoint_exclude)?,
+            fstype_include: Regex::new(fstype_include)?,
+            fstype_exclude: Regex::new(fstype_exclude)?,
+            mount_timeout,
+            stuck_mounts: Arc::new(Mutex::new(HashSet::new())),
+        })
+    }
+
+    fn is_included(&self, mount: &MountEntry) -> bool {
+        self.mountpoint_include.is_match(&mount.mountpoint)
+            && !self.mountpoint_exclude.is_match(&mount.mountpoint)
+            && self.fstype_include.is_match(&mount.fstype)
+            && !self.fstype_exclude.is_match(&mount.fstype)
+    }
+
+    /// Run statvfs on a separate thread so that a hung mount (e.g. an
+    /// unreachable NFS server) cannot block the scrape longer than the
+    /// configured timeout.
+    fn statvfs_with_timeout(&self, mountpoint: &str) -> io::Result<FilesystemUsage> {
+        // The set only holds mountpoint names, so it is still consistent if
+        // another thread panicked while holding the lock.
+        let is_stuck = self
+            .stuck_mounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(mountpoint);
+        if is_stuck {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "previous statvfs call has not returned",
+            ));
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let stuck_mounts = Arc::clone(&self.stuck_mounts);
+        let path = mountpoint.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(statvfs(&path));
+            stuck_mounts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&path);
+        });
+
+        match rx.recv_timeout(self.mount_timeout) {
+            Ok(result) => result,
+            Err(_) => {
+                // Hold the lock while re-checking so the worker cannot finish
+                // between the check and the insert and leave the mount marked.
+                let mut stuck = self
+                    .stuck_mounts
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                if let Ok(result) = rx.try_recv() {
+                    return result;
+                }
+                stuck.insert(mountpoint.to_string());
+                Err(io::Error::new(io::ErrorKind::TimedOut, "statvfs timed out"))
+            }
+        }
+    }
+}
+
+impl Collector for FilesystemCollector {
+    fn name(&self) -> &'static str {
+        "filesystem"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        let content =
+            fs::read_to_string(PROC_MOUNTS_PATH).map_err(|e| CollectorError::FileRead {
+                path: PROC_MOUNTS_PATH.to_string(),
+                source: e,
+            })?;
+        self.collect_from_string(&content)
+    }
+}
+
+impl FilesystemCollector {
+    pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+        for mount in parse_mounts(content)? {
+            // A mountpoint can appear more than once when mounts are stacked;
+            // only the first entry is reported.
+            if !self.is_included(&mount) || !seen.insert(mount.mountpoint.clone()) {
+                continue;
+            }
+            let usage = self.statvfs_with_timeout(&mount.mountpoint);
+            if let Err(ref e) = usage {
+                tracing::debug!(mountpoint = %mount.mountpoint, error = %e, "statvfs failed");
+            }
+            results.push((mount, usage.ok()));
+        }
+        Ok(filesystem_metrics(&results))
+    }
+}
+
+/// Build metric families from mount entries and their usage. Mounts whose
+/// usage could not be determined only appear in the device error gauge.
+pub fn filesystem_metrics(results: &[(MountEntry, Option<FilesystemUsage>)]) -> Vec<Metric> {
+    let labels = |m: &MountEntry| {
+        vec![
+            ("device".to_string(), m.device.clone()),
+            ("mountpoint".to_string(), m.mountpoint.clone()),
+            ("fstype".to_string(), m.fstype.clone()),
+        ]
+    };
+
+    type MetricDef = (
+        &'static str,
+        &'static str,
+        Box<dyn Fn(&FilesystemUsage) -> f64>,
+    );
+    let metric_defs: Vec<MetricDef> = vec![
+        (
+            "sysmetrics_filesystem_size_bytes",
+            "Filesystem size in bytes.",
+            Box::new(|u: &FilesystemUsage| u.size_bytes as f64),
+        ),
+        (
+            "sysmetrics_filesystem_free_bytes",
+            "Filesystem free space in bytes.",
+            Box::new(|u: &FilesystemUsage| u.free_bytes as f64),
+        ),
+        (
+            "sysmetrics_filesystem_avail_bytes",
+            "Filesystem space available to non-root users in bytes.",
+            Box::new(|u: &FilesystemUsage| u.avail_bytes as f64),
+        ),
+        (
+            "sysmetrics_filesystem_files",
+            "Filesystem total file nodes.",
+            Box::new(|u: &FilesystemUsage| u.files as f64),
+        ),
+        (
+            "sysmetrics_filesystem_files_free",
+            "Filesystem free file nodes.",
+            Box::new(|u: &FilesystemUsage| u.files_free as f64),
+        ),
+    ];
+
+    let mut metrics = Vec::new();
+    for (name, help, value_fn) in &metric_defs {
+        let samples = results
+            .iter()
+            .filter_map(|(m, usage)| {
+                usage.as_ref().map(|u| MetricSample {
+                    labels: labels(m),
+                    value: value_fn(u),
+                })
+            })
+            .collect();
+        metrics.push(Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Gauge,
+            samples,
+        });
+    }
+
+    metrics.push(Metric {
+        name: "sysmetrics_filesystem_device_error".to_string(),
+        help: "Whether an error occurred while getting statistics for the filesystem.".to_string(),
+        metric_type: MetricType::Gauge,
+        samples: results
+            .iter()
+            .map(|(m, usage)| MetricSample {
+                labels: labels(m),
+                value: if usage.is_some() { 0.0 } else { 1.0 },
+            })
+            .collect(),
+    });
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS_FIXTURE: &str = "\
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+/dev/sda1 / ext4 rw,relatime,errors=remount-ro 0 0
+/dev/sda2 /home ext4 rw,relatime 0 0
+tmpfs /run tmpfs rw,nosuid,nodev,noexec,relatime,size=1634564k,mode=755 0 0
+/dev/sdb1 /mnt/my\\040disk xfs rw,relatime 0 0
+nfs.example.com:/export /mnt/nfs nfs4 rw,relatime,vers=4.2 0 0
+";
+
+    fn default_collector() -> FilesystemCollector {
+        FilesystemCollector::new(
+            "",
+            "^/(dev|proc|run|sys)($|/)",
+            "",
+            "^(proc|sysfs)$",
+            Duration::from_secs(5),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_mounts() {
+        let mounts = parse_mounts(MOUNTS_FIXTURE).unwrap();
+        assert_eq!(mounts.len(), 7);
+        assert_eq!(
+            mounts[2],
+            MountEntry {
+                device: "/dev/sda1".to_string(),
+                mountpoint: "/".to_string(),
+                fstype: "ext4".to_string(),
+            }
+        );
+        assert_eq!(mounts[6].device, "nfs.example.com:/export");
+        assert_eq!(mounts[6].fstype, "nfs4");
+    }
+
+    #[test]
+    fn test_parse_mounts_octal_escape() {
+        let mounts = parse_mounts(MOUNTS_FIXTURE).unwrap();
+        assert_eq!(mounts[5].mountpoint, "/mnt/my disk");
+    }
+
+    #[test]
+    fn test_parse_mounts_malformed() {
+        assert!(parse_mounts("/dev/sda1 /\n").is_err());
+    }
+
+    #[test]
+    fn test_filesystem_collector_filters() {
+        let collector = default_collector();
+        let included: Vec<String> = parse_mounts(MOUNTS_FIXTURE)
+            .unwrap()
+            .into_iter()
+            .filter(|m| collector.is_included(m))
+            .map(|m| m.mountpoint)
+            .collect();
+        assert_eq!(included, vec!["/", "/home", "/mnt/my disk", "/mnt/nfs"]);
+    }
+
+    #[test]
+    fn test_filesystem_collector_fstype_include() {
+        let collector =
+            FilesystemCollector::new("", "^$", "^nfs", "^$", Duration::from_secs(5)).unwrap();
+        let included: Vec<String> = parse_mounts(MOUNTS_FIXTURE)
+            .unwrap()
+            .into_iter()
+            .filter(|m| collector.is_included(m))
+            .map(|m| m.mountpoint)
+            .collect();
+        assert_eq!(included, vec!["/mnt/nfs"]);
+    }
+
+    #[test]
+    fn test_filesystem_metrics_values() {
+        let mount = MountEntry {
+            device: "/dev/sda1".to_string(),
+            mountpoint: "/".to_string(),
+            fstype: "ext4".to_string(),
+        };
+        let usage = FilesystemUsage {
+            size_bytes: 1000,
+            free_bytes: 400,
+            avail_bytes: 300,
+            files: 64,
+            files_free: 32,
+        };
+        let failed = MountEntry {
+            device: "nfs.example.com:/export".to_string(),
+            mountpoint: "/mnt/nfs".to_string(),
+            fstype: "nfs4".to_string(),
+        };
+        let metrics = filesystem_metrics(&[(mount, Some(usage)), (failed, None)]);
+        assert_eq!(metrics.len(), 6);
+
+        assert_eq!(metrics[0].name, "sysmetrics_filesystem_size_bytes");
+        assert_eq!(metrics[0].samples.len(), 1);
+        assert_eq!(metrics[0].samples[0].value, 1000.0);
+        assert_eq!(
+            metrics[0].samples[0].labels,
+            vec![
+                ("device".to_string(), "/dev/sda1".to_string()),
+                ("mountpoint".to_string(), "/".to_string()),
+                ("fstype".to_string(), "ext4".to_string()),
+            ]
+        );
+        assert_eq!(metrics[2].name, "sysmetrics_filesystem_avail_bytes");
+        assert_eq!(metrics[2].samples[0].value, 300.0);
+        assert_eq!(metrics[4].samples[0].value, 32.0);
+
+        let errors = &metrics[5];
+        assert_eq!(errors.name, "sysmetrics_filesystem_device_error");
+        assert_eq!(errors.samples[0].value, 0.0);
+        assert_eq!(errors.samples[1].value, 1.0);
+    }
+
+    #[test]
+    fn test_filesystem_collector_statvfs_real_path() {
+        let collector = default_collector();
+        // The crate directory always exists; its size depends on the host,
+        // so only the presence of a sample is checked.
+        let input = format!(
+            "/dev/sda1 {} ext4 rw 0 0\n/dev/sdz9 /nonexistent/sysmetrics-test ext4 rw 0 0\n",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let metrics = collector.collect_from_string(&input).unwrap();
+        assert_eq!(metrics[0].samples.len(), 1);
+        assert_eq!(metrics[5].samples[0].value, 0.0);
+        assert_eq!(metrics[5].samples[1].value, 1.0);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::collector::{
+    list_sysfs_dir, read_sysfs_string, read_sysfs_value, Collector, Metric, MetricSample,
+    MetricType,
+};
+use crate::error::CollectorError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const SYS_CLASS_HWMON_PATH: &str = "/sys/class/hwmon";
+
+/// A sysfs sensor attribute exported as a metric family. `divisor` converts
+/// the raw sysfs unit to the base unit (millidegrees, millivolts and
+/// microwatts).
+struct SensorDef {
+    kind: &'static str,
+    attribute: &'static str,
+    name: &'static str,
+    help: &'static str,
+    divisor: f64,
+}
+
+const SENSOR_DEFS: &[SensorDef] = &[
+    SensorDef {
+        kind: "temp",
+        attribute: "input",
+        name: "sysmetrics_hwmon_temp_celsius",
+        help: "Hardware monitor temperature reading in degrees celsius.",
+        divisor: 1000.0,
+    },
+    SensorDef {
+        kind: "temp",
+        attribute: "crit",
+        name: "sysmetrics_hwmon_temp_crit_celsius",
+        help: "Hardware monitor critical temperature threshold in degrees celsius.",
+        divisor: 1000.0,
+    },
+    SensorDef {
+        kind: "temp",
+        attribute: "max",
+        name: "sysmetrics_hwmon_temp_max_celsius",
+        help: "Hardware monitor maximum temperature threshold in degrees celsius.",
+        divisor: 1000.0,
+    },
+    SensorDef {
+        kind: "fan",
+        attribute: "input",
+        name: "sysmetrics_hwmon_fan_rpm",
+        help: "Hardware monitor fan speed in revolutions per minute.",
+        divisor: 1.0,
+    },
+    SensorDef {
+        kind: "in",
+        attribute: "input",
+        name: "sysmetrics_hwmon_in_volts",
+        help: "Hardware monitor voltage reading in volts.",
+        divisor: 1000.0,
+    },
+    SensorDef {
+        kind: "power",
+        attribute: "input",
+        name: "sysmetrics_hwmon_power_watts",
+        help: "Hardware monitor power reading in watts.",
+        divisor: 1_000_000.0,
+    },
+];
+
+/// Split a sensor attribute file name such as `temp1_input` into its kind
+/// (`temp`), channel (`temp1`) and attribute (`input`).
+fn parse_sensor_file(file_name: &str) -> Option<(&str, &str, &str)> {
+    let (channel, attribute) = file_name.split_once('_')?;
+    let kind = channel.trim_end_matches(|c: char| c.is_ascii_digit());
+    if kind.is_empty() || kind.len() == channel.len() {
+        return None;
+    }
+    Some((kind, channel, attribute))
+}
+
+/// A single sensor reading, already converted to base units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    /// Channel name such as `temp1`, unique within a chip.
+    pub sensor: String,
+    pub metric: &'static str,
+    pub value: f64,
+}
+
+/// A hwmon chip and its readings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HwmonChip {
+    /// Driver name from the `name` attribute, e.g. `coretemp`.
+    pub name: String,
+    /// Name of the parent device, e.g. `coretemp.0` or `0000:03:00.0`.
+    /// Unlike the `hwmonN` number it does not depend on driver load order.
+    pub device: String,
+    pub readings: Vec<SensorReading>,
+    /// Channel name to the content of its `*_label` file. Labels are not
+    /// unique within a chip, so they are exported separately.
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Read all supported sensor attributes of a single hwmon chip directory.
+pub fn read_hwmon_chip(dir: &Path) -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut file_names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect();
+    file_names.sort();
+
+    let mut readings = Vec::new();
+    for file_name in &file_names {
+        let Some((kind, channel, attribute)) = parse_sensor_file(file_name) else {
+            continue;
+        };
+        let Some(def) = SENSOR_DEFS
+            .iter()
+            .find(|d| d.kind == kind && d.attribute == attribute)
+        else {
+            continue;
+        };
+        // Drivers return errors (e.g. ENODATA) for sensors that are
+        // present but not currently readable; those are skipped.
+        let Some(raw) = read_sysfs_value::<i64>(&dir.join(file_name)) else {
+            continue;
+        };
+        readings.push(SensorReading {
+            sensor: channel.to_string(),
+            metric: def.name,
+            value: raw as f64 / def.divisor,
+        });
+    }
+    readings
+}
+
+/// Read the `*_label` files of a hwmon chip directory, keyed by channel.
+pub fn read_sensor_labels(dir: &Path) -> BTreeMap<String, String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter_map(|file_name| {
+            let (_, channel, attribute) = parse_sensor_file(&file_name)?;
+            if attribute != "label" {
+                return None;
+            }
+            let label = read_sysfs_string(&dir.join(&file_name)).filter(|l| !l.is_empty())?;
+            Some((channel.to_string(), label))
+        })
+        .collect()
+}
+
+/// Read the chip behind a `/sys/class/hwmon/hwmonN` entry.
+pub fn read_hwmon(hwmon_dir: &Path) -> HwmonChip {
+    let hwmon_name = hwmon_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    // Older drivers expose their attributes on the parent device rather
+    // than on the hwmon class device itself.
+    let dir = if hwmon_dir.join("name").exists() {
+        hwmon_dir.to_path_buf()
+    } else {
+        hwmon_dir.join("device")
+    };
+    // Virtual chips without a parent device keep the hwmonN name.
+    let device = fs::read_link(hwmon_dir.join("device"))
+        .ok()
+        .and_then(|target| Some(target.file_name()?.to_str()?.to_string()))
+        .unwrap_or_else(|| hwmon_name.clone());
+    HwmonChip {
+        name: read_sysfs_string(&dir.join("name")).unwrap_or(hwmon_name),
+        device,
+        readings: read_hwmon_chip(&dir),
+        labels: read_sensor_labels(&dir),
+    }
+}
+
+pub struct HwmonCollector;
+
+impl Collector for HwmonCollector {
+    fn name(&self) -> &'static str {
+        "hwmon"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(SYS_CLASS_HWMON_PATH))
+    }
+}
+
+impl HwmonCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        let chips: Vec<HwmonChip> = list_sysfs_dir(root, "hwmon")?
+            .iter()
+            .map(|dir| read_hwmon(dir))
+            .collect();
+        let chip_labels = |chip: &HwmonChip, sensor: &str| {
+            vec![
+                ("chip".to_string(), chip.name.clone()),
+                ("device".to_string(), chip.device.clone()),
+                ("sensor".to_string(), sensor.to_string()),
+            ]
+        };
+
+        let mut metrics: Vec<Metric> = SENSOR_DEFS
+            .iter()
+            .map(|def| Metric {
+                name: def.name.to_string(),
+                help: def.help.to_string(),
+                metric_type: MetricType::Gauge,
+                samples: chips
+                    .iter()
+                    .flat_map(|chip| {
+                        chip.readings
+                            .iter()
+                            .filter(|r| r.metric == def.name)
+                            .map(|r| MetricSample {
+                                labels: chip_labels(chip, &r.sensor),
+                                value: r.value,
+                            })
+                    })
+                    .collect(),
+            })
+            .collect();
+        metrics.push(Metric {
+            name: "sysmetrics_hwmon_sensor_label".to_string(),
+            help: "Label of a hardware monitor sensor channel from its *_label file.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: chips
+                .iter()
+                .flat_map(|chip| {
+                    chip.labels.iter().map(|(sensor, label)| {
+                        let mut labels = chip_labels(chip, sensor);
+                        labels.push(("label".to_string(), label.clone()));
+                        MetricSample { labels, value: 1.0 }
+                    })
+                })
+                .collect(),
+        });
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const HWMON_FIXTURE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/sys/class/hwmon"
+    );
+
+    fn labels(chip: &str, device: &str, sensor: &str) -> Vec<(String, String)> {
+        vec![
+            ("chip".to_string(), chip.to_string()),
+            ("device".to_string(), device.to_string()),
+            ("sensor".to_string(), sensor.to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_parse_sensor_file() {
+        assert_eq!(
+            parse_sensor_file("temp1_input"),
+            Some(("temp", "temp1", "input"))
+        );
+        assert_eq!(parse_sensor_file("in0_label"), Some(("in", "in0", "label")));
+        assert_eq!(parse_sensor_file("name"), None);
+        assert_eq!(parse_sensor_file("device_uevent"), None);
+        assert_eq!(parse_sensor_file("10_input"), None);
+    }
+
+    #[test]
+    fn test_hwmon_collector_temperatures() {
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+        let temps = find_metric(&metrics, "sysmetrics_hwmon_temp_celsius");
+        assert_eq!(temps.metric_type, MetricType::Gauge);
+        assert_eq!(temps.samples.len(), 5);
+        assert_eq!(
+            temps.samples[0].labels,
+            labels("coretemp", "coretemp.0", "temp1")
+        );
+        assert_eq!(temps.samples[0].value, 45.0);
+        assert_eq!(
+            temps.samples[1].labels,
+            labels("coretemp", "coretemp.0", "temp2")
+        );
+        assert_eq!(temps.samples[1].value, 42.5);
+
+        let crit = find_metric(&metrics, "sysmetrics_hwmon_temp_crit_celsius");
+        assert_eq!(crit.samples.len(), 1);
+        assert_eq!(crit.samples[0].value, 100.0);
+
+        let max = find_metric(&metrics, "sysmetrics_hwmon_temp_max_celsius");
+        assert_eq!(max.samples[0].value, 80.0);
+    }
+
+    #[test]
+    fn test_hwmon_collector_duplicate_chip_names() {
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+        let temps = find_metric(&metrics, "sysmetrics_hwmon_temp_celsius");
+        // hwmon2 is a second "coretemp" (second CPU package), told apart by
+        // its parent device rather than by enumeration order.
+        assert_eq!(
+            temps.samples[2].labels,
+            labels("coretemp", "coretemp.1", "temp1")
+        );
+        assert_eq!(temps.samples[2].value, 47.0);
+    }
+
+    #[test]
+    fn test_hwmon_collector_fans_voltages_power() {
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+
+        let fans = find_metric(&metrics, "sysmetrics_hwmon_fan_rpm");
+        assert_eq!(fans.samples.len(), 2);
+        // No device link: the hwmonN name identifies the chip.
+        assert_eq!(fans.samples[0].labels, labels("nct6775", "hwmon1", "fan1"));
+        assert_eq!(fans.samples[0].value, 1250.0);
+
+        let volts = find_metric(&metrics, "sysmetrics_hwmon_in_volts");
+        assert_eq!(volts.samples.len(), 2);
+        assert_eq!(volts.samples[0].labels, labels("nct6775", "hwmon1", "in0"));
+        assert!((volts.samples[0].value - 0.912).abs() < 1e-9);
+
+        let power = find_metric(&metrics, "sysmetrics_hwmon_power_watts");
+        assert_eq!(power.samples.len(), 1);
+        assert_eq!(
+            power.samples[0].labels,
+            labels("amdgpu", "0000:03:00.0", "power1")
+        );
+        assert_eq!(power.samples[0].value, 35.5);
+    }
+
+    #[test]
+    fn test_hwmon_collector_unreadable_sensor_skipped() {
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+        // amdgpu temp1_input holds garbage and is skipped.
+        let temps = find_metric(&metrics, "sysmetrics_hwmon_temp_celsius");
+        assert!(!temps
+            .samples
+            .iter()
+            .any(|s| s.labels[0].1 == "amdgpu" && s.labels[2].1 == "temp1"));
+    }
+
+    #[test]
+    fn test_hwmon_collector_sensor_labels() {
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+        let sensor_labels = find_metric(&metrics, "sysmetrics_hwmon_sensor_label");
+        let mut with_label = labels("coretemp", "coretemp.0", "temp1");
+        with_label.push(("label".to_string(), "Package id 0".to_string()));
+        assert_eq!(sensor_labels.samples[0].labels, with_label);
+
+        // nct6775 labels in0 and in1 both "Vcore"; the voltage series stay
+        // distinct because they are keyed by channel.
+        let vcore: Vec<&str> = sensor_labels
+            .samples
+            .iter()
+            .filter(|s| s.labels[3].1 == "Vcore")
+            .map(|s| s.labels[2].1.as_str())
+            .collect();
+        assert_eq!(vcore, vec!["in0", "in1"]);
+        let mut series: Vec<_> = metrics
+            .iter()
+            .flat_map(|m| m.samples.iter().map(move |s| (&m.name, &s.labels)))
+            .collect();
+        let count = series.len();
+        series.sort();
+        series.dedup();
+        assert_eq!(series.len(), count);
+    }
+
+    #[test]
+    fn test_hwmon_collector_device_fallback() {
+        // hwmon4 has no attributes of its own; they live on the parent
+        // device.
+        let metrics = HwmonCollector
+            .collect_from_dir(Path::new(HWMON_FIXTURE_DIR))
+            .unwrap();
+        let temps = find_metric(&metrics, "sysmetrics_hwmon_temp_celsius");
+        let w83627 = temps.samples.last().unwrap();
+        assert_eq!(w83627.labels, labels("w83627hf", "w83627hf.656", "temp1"));
+        assert_eq!(w83627.value, 38.0);
+    }
+
+    #[test]
+    fn test_hwmon_collector_missing_root() {
+        assert_missing_root_not_supported(|root| HwmonCollector.collect_from_dir(root));
+    }
+}
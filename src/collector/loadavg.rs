@@ -0,0 +1,271 @@
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::fs;
+
+const PROC_LOADAVG_PATH: &str = "/proc/loadavg";
+const PROC_UPTIME_PATH: &str = "/proc/uptime";
+const PROC_STAT_PATH: &str = "/proc/stat";
+
+/// Parsed /proc/loadavg content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadAvg {
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+    pub runnable: u64,
+    pub total: u64,
+    pub last_pid: u64,
+}
+
+/// Parsed /proc/uptime content, both in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uptime {
+    pub uptime: f64,
+    /// Idle time summed over all CPUs, so it can exceed `uptime`.
+    pub idle: f64,
+}
+
+/// Parse /proc/loadavg content, e.g. "0.52 0.58 0.59 2/1143 123456".
+pub fn parse_loadavg(content: &str) -> Result<LoadAvg, CollectorError> {
+    let parse_err = |field: &str, raw: &str| CollectorError::Parse {
+        path: PROC_LOADAVG_PATH.to_string(),
+        field: field.to_string(),
+        raw: raw.to_string(),
+    };
+
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    if parts.len() < 5 {
+        return Err(parse_err("loadavg line", content.trim()));
+    }
+    let parse_f64 = |idx: usize, field: &str| -> Result<f64, CollectorError> {
+        parts[idx]
+            .parse::<f64>()
+            .map_err(|_| parse_err(field, parts[idx]))
+    };
+    let parse_u64 = |raw: &str, field: &str| -> Result<u64, CollectorError> {
+        raw.parse::<u64>().map_err(|_| parse_err(field, raw))
+    };
+
+    let (runnable, total) = parts[3]
+        .split_once('/')
+        .ok_or_else(|| parse_err("scheduling entities", parts[3]))?;
+
+    Ok(LoadAvg {
+        load1: parse_f64(0, "load1")?,
+        load5: parse_f64(1, "load5")?,
+        load15: parse_f64(2, "load15")?,
+        runnable: parse_u64(runnable, "runnable entities")?,
+        total: parse_u64(total, "total entities")?,
+        last_pid: parse_u64(parts[4], "last pid")?,
+    })
+}
+
+/// Parse /proc/uptime content, e.g. "350735.47 234388.90".
+pub fn parse_uptime(content: &str) -> Result<Uptime, CollectorError> {
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    if parts.len() < 2 {
+        return Err(CollectorError::Parse {
+            path: PROC_UPTIME_PATH.to_string(),
+            field: "uptime line".to_string(),
+            raw: content.trim().to_string(),
+        });
+    }
+    let parse_field = |idx: usize, field: &str| -> Result<f64, CollectorError> {
+        parts[idx]
+            .parse::<f64>()
+            .map_err(|_| CollectorError::Parse {
+                path: PROC_UPTIME_PATH.to_string(),
+                field: field.to_string(),
+                raw: parts[idx].to_string(),
+            })
+    };
+    Ok(Uptime {
+        uptime: parse_field(0, "uptime")?,
+        idle: parse_field(1, "idle")?,
+    })
+}
+
+/// Extract the boot time (`btime`, seconds since the epoch) from /proc/stat.
+pub fn parse_boot_time(content: &str) -> Result<u64, CollectorError> {
+    let line = content
+        .lines()
+        .find(|l| l.starts_with("btime "))
+        .ok_or_else(|| CollectorError::Parse {
+            path: PROC_STAT_PATH.to_string(),
+            field: "btime".to_string(),
+            raw: "btime line not found".to_string(),
+        })?;
+    let raw = line.split_whitespace().nth(1).unwrap_or_default();
+    raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+        path: PROC_STAT_PATH.to_string(),
+        field: "btime".to_string(),
+        raw: raw.to_string(),
+    })
+}
+
+fn read_file(path: &str) -> Result<String, CollectorError> {
+    fs::read_to_string(path).map_err(|e| CollectorError::FileRead {
+        path: path.to_string(),
+        source: e,
+    })
+}
+
+pub struct LoadAvgCollector;
+
+impl Collector for LoadAvgCollector {
+    fn name(&self) -> &'static str {
+        "loadavg"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        let loadavg = read_file(PROC_LOADAVG_PATH)?;
+        let uptime = read_file(PROC_UPTIME_PATH)?;
+        let stat = read_file(PROC_STAT_PATH)?;
+        self.collect_from_string(&loadavg, &uptime, &stat)
+    }
+}
+
+impl LoadAvgCollector {
+    pub fn collect_from_string(
+        &self,
+        loadavg: &str,
+        uptime: &str,
+        stat: &str,
+    ) -> Result<Vec<Metric>, CollectorError> {
+        let load = parse_loadavg(loadavg)?;
+        let uptime = parse_uptime(uptime)?;
+        let boot_time = parse_boot_time(stat)?;
+
+        let metrics = vec![
+            ("sysmetrics_load1", "1-minute load average.", load.load1),
+            ("sysmetrics_load5", "5-minute load average.", load.load5),
+            ("sysmetrics_load15", "15-minute load average.", load.load15),
+            (
+                "sysmetrics_load_runnable_entities",
+                "Number of currently runnable kernel scheduling entities.",
+                load.runnable as f64,
+            ),
+            (
+                "sysmetrics_load_scheduling_entities",
+                "Number of kernel scheduling entities (processes and threads).",
+                load.total as f64,
+            ),
+            (
+                "sysmetrics_load_last_pid",
+                "PID of the most recently created process.",
+                load.last_pid as f64,
+            ),
+            (
+                "sysmetrics_boot_time_seconds",
+                "System boot time in seconds since the Unix epoch.",
+                boot_time as f64,
+            ),
+            (
+                "sysmetrics_uptime_seconds",
+                "Time since boot in seconds.",
+                uptime.uptime,
+            ),
+            (
+                "sysmetrics_uptime_idle_seconds",
+                "Idle time since boot in seconds, summed over all CPUs.",
+                uptime.idle,
+            ),
+        ];
+
+        Ok(metrics
+            .into_iter()
+            .map(|(name, help, value)| Metric {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric_type: MetricType::Gauge,
+                samples: vec![MetricSample {
+                    labels: vec![],
+                    value,
+                }],
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOADAVG_FIXTURE: &str = "0.52 0.58 0.59 2/1143 123456\n";
+    const UPTIME_FIXTURE: &str = "350735.47 234388.90\n";
+    const STAT_FIXTURE: &str = "\
+cpu  74156 1260 22706 6316498 4539 0 456 0 0 0
+cpu0 18539 315 5676 1579124 1134 0 114 0 0 0
+intr 12345678
+ctxt 987654321
+btime 1700000000
+processes 54321
+";
+
+    #[test]
+    fn test_parse_loadavg() {
+        let load = parse_loadavg(LOADAVG_FIXTURE).unwrap();
+        assert_eq!(
+            load,
+            LoadAvg {
+                load1: 0.52,
+                load5: 0.58,
+                load15: 0.59,
+                runnable: 2,
+                total: 1143,
+                last_pid: 123456,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_loadavg_malformed() {
+        assert!(parse_loadavg("0.52 0.58\n").is_err());
+        assert!(parse_loadavg("0.52 0.58 0.59 21143 123456\n").is_err());
+        assert!(parse_loadavg("a b c 1/2 3\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_uptime() {
+        let uptime = parse_uptime(UPTIME_FIXTURE).unwrap();
+        assert!((uptime.uptime - 350735.47).abs() < 0.001);
+        assert!((uptime.idle - 234388.90).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_uptime_malformed() {
+        assert!(parse_uptime("").is_err());
+        assert!(parse_uptime("abc def").is_err());
+    }
+
+    #[test]
+    fn test_parse_boot_time() {
+        assert_eq!(parse_boot_time(STAT_FIXTURE).unwrap(), 1700000000);
+    }
+
+    #[test]
+    fn test_parse_boot_time_missing() {
+        assert!(parse_boot_time("cpu  1 2 3 4 5 6 7 8 9 10\n").is_err());
+    }
+
+    #[test]
+    fn test_loadavg_collector_metrics() {
+        let collector = LoadAvgCollector;
+        let metrics = collector
+            .collect_from_string(LOADAVG_FIXTURE, UPTIME_FIXTURE, STAT_FIXTURE)
+            .unwrap();
+        assert_eq!(metrics.len(), 9);
+
+        assert_eq!(metrics[0].name, "sysmetrics_load1");
+        assert_eq!(metrics[0].metric_type, MetricType::Gauge);
+        assert_eq!(metrics[0].samples[0].value, 0.52);
+        assert_eq!(metrics[2].name, "sysmetrics_load15");
+        assert_eq!(metrics[2].samples[0].value, 0.59);
+
+        assert_eq!(metrics[6].name, "sysmetrics_boot_time_seconds");
+        assert_eq!(metrics[6].samples[0].value, 1700000000.0);
+
+        assert_eq!(metrics[7].name, "sysmetrics_uptime_seconds");
+        assert!((metrics[7].samples[0].value - 350735.47).abs() < 0.001);
+    }
+}
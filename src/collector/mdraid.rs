@@ -0,0 +1,495 @@
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::fs;
+use std::io;
+
+const PROC_MDSTAT_PATH: &str = "/proc/mdstat";
+
+/// Array states exported for every array.
+const MD_STATES: &[&str] = &["active", "inactive", "read_only"];
+
+/// Sync actions as named in the progress line of /proc/mdstat.
+const SYNC_ACTIONS: &[&str] = &["resync", "recovery", "reshape", "check", "repair"];
+
+/// A resync, recovery, reshape or check running (or queued) on an array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdSync {
+    pub action: String,
+    /// Completed fraction from 0 to 1.
+    pub progress: f64,
+    /// `None` while the action is `DELAYED` or `PENDING`.
+    pub speed_bytes: Option<f64>,
+    pub finish_seconds: Option<f64>,
+}
+
+/// A single md array from /proc/mdstat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdArray {
+    pub device: String,
+    /// One of `MD_STATES`.
+    pub state: String,
+    /// Personality such as `raid1`; empty for inactive arrays.
+    pub level: String,
+    /// Disks the array needs to be complete: the `n` of `[n/m]`, or the
+    /// number of members for levels without redundancy (raid0, linear).
+    pub disks_required: u64,
+    /// Working disks: the `m` of `[n/m]`.
+    pub disks_active: u64,
+    pub disks_failed: u64,
+    pub disks_spare: u64,
+    /// Disks being rebuilt to replace a member, marked `(R)`.
+    pub disks_replacement: u64,
+    /// raid4/5/6 write journal devices, marked `(J)`.
+    pub disks_journal: u64,
+    pub sync: Option<MdSync>,
+}
+
+fn parse_error(field: &str, raw: &str) -> CollectorError {
+    CollectorError::Parse {
+        path: PROC_MDSTAT_PATH.to_string(),
+        field: field.to_string(),
+        raw: raw.to_string(),
+    }
+}
+
+/// Parse the `[n/m]` disk count of an array status line, if present.
+fn parse_disk_counts(line: &str) -> Result<Option<(u64, u64)>, CollectorError> {
+    let Some(token) = line
+        .split_whitespace()
+        .find(|t| t.starts_with('[') && t.ends_with(']') && t.contains('/'))
+    else {
+        return Ok(None);
+    };
+    let (total, active) = token[1..token.len() - 1]
+        .split_once('/')
+        .ok_or_else(|| parse_error("disks", line))?;
+    Ok(Some((
+        total.parse().map_err(|_| parse_error("disks", line))?,
+        active.parse().map_err(|_| parse_error("disks", line))?,
+    )))
+}
+
+/// Parse a progress line such as
+/// `[==>....]  recovery = 12.6% (37043392/293039104) finish=127.5min speed=33440K/sec`
+/// or `resync=DELAYED`. Returns `None` for other lines.
+fn parse_sync_line(line: &str) -> Result<Option<MdSync>, CollectorError> {
+    let line = line.trim_start_matches(|c: char| !c.is_ascii_lowercase());
+    let Some((action, rest)) = line.split_once('=') else {
+        return Ok(None);
+    };
+    let action = action.trim();
+    if !SYNC_ACTIONS.contains(&action) {
+        return Ok(None);
+    }
+    let rest = rest.trim();
+    if rest == "DELAYED" || rest == "PENDING" {
+        return Ok(Some(MdSync {
+            action: action.to_string(),
+            progress: 0.0,
+            speed_bytes: None,
+            finish_seconds: None,
+        }));
+    }
+
+    let mut progress = None;
+    let mut speed_bytes = None;
+    let mut finish_seconds = None;
+    for token in rest.split_whitespace() {
+        if let Some(blocks) = token.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            // The block counts are more precise than the rounded percentage.
+            if let Some((done, total)) = blocks.split_once('/') {
+                let done: f64 = done.parse().map_err(|_| parse_error(action, line))?;
+                let total: f64 = total.parse().map_err(|_| parse_error(action, line))?;
+                if total > 0.0 {
+                    progress = Some(done / total);
+                }
+            }
+        } else if let Some(percent) = token.strip_suffix('%') {
+            if progress.is_none() {
+                let percent: f64 = percent.parse().map_err(|_| parse_error(action, line))?;
+                progress = Some(percent / 100.0);
+            }
+        } else if let Some(minutes) = token
+            .strip_prefix("finish=")
+            .and_then(|t| t.strip_suffix("min"))
+        {
+            let minutes: f64 = minutes.parse().map_err(|_| parse_error("finish", line))?;
+            finish_seconds = Some(minutes * 60.0);
+        } else if let Some(kib) = token
+            .strip_prefix("speed=")
+            .and_then(|t| t.strip_suffix("K/sec"))
+        {
+            let kib: f64 = kib.parse().map_err(|_| parse_error("speed", line))?;
+            speed_bytes = Some(kib * 1024.0);
+        }
+    }
+    Ok(Some(MdSync {
+        action: action.to_string(),
+        progress: progress.ok_or_else(|| parse_error(action, line))?,
+        speed_bytes,
+        finish_seconds,
+    }))
+}
+
+/// Parse the `mdN : active raid1 sdb1[1] sda1[0](F)` line that starts an
+/// array. Disk counts are filled in from the member list and refined by
+/// the status line.
+///
+/// Members can carry several flags such as `(W)(F)`. Spares, replacements
+/// and journal devices are not among the disks the array requires.
+fn parse_array_header(line: &str) -> Result<MdArray, CollectorError> {
+    let (device, rest) = line
+        .split_once(" : ")
+        .ok_or_else(|| parse_error("array", line))?;
+    let mut tokens = rest.split_whitespace().peekable();
+    let mut state = match tokens.next() {
+        Some("active") => "active",
+        Some("inactive") => "inactive",
+        _ => return Err(parse_error("state", line)),
+    };
+    // `(auto-read-only)` or `(read-only)`
+    if tokens.peek().is_some_and(|t| t.starts_with('(')) {
+        tokens.next();
+        state = "read_only";
+    }
+    let level = match tokens.peek() {
+        Some(t) if !t.contains('[') => tokens.next().unwrap_or_default().to_string(),
+        _ => String::new(),
+    };
+
+    let (mut members, mut failed, mut spare, mut replacement, mut journal) = (0, 0, 0, 0, 0);
+    for member in tokens {
+        if member.contains("(F)") {
+            failed += 1;
+        } else if member.contains("(J)") {
+            journal += 1;
+            continue;
+        } else if member.contains("(S)") {
+            spare += 1;
+            continue;
+        } else if member.contains("(R)") {
+            replacement += 1;
+            continue;
+        }
+        members += 1;
+    }
+    Ok(MdArray {
+        device: device.trim().to_string(),
+        state: state.to_string(),
+        level,
+        disks_required: members,
+        disks_active: if state == "inactive" {
+            0
+        } else {
+            members - failed
+        },
+        disks_failed: failed,
+        disks_spare: spare,
+        disks_replacement: replacement,
+        disks_journal: journal,
+        sync: None,
+    })
+}
+
+/// Parse /proc/mdstat into its arrays.
+pub fn parse_mdstat(content: &str) -> Result<Vec<MdArray>, CollectorError> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with("Personalities") || line.starts_with("unused devices") {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && line.contains(" : ") {
+            arrays.push(parse_array_header(line)?);
+            continue;
+        }
+        let Some(array) = arrays.last_mut() else {
+            continue;
+        };
+        if let Some((total, active)) = parse_disk_counts(line)? {
+            array.disks_required = total;
+            array.disks_active = active;
+        } else if let Some(sync) = parse_sync_line(line)? {
+            array.sync = Some(sync);
+        }
+    }
+    Ok(arrays)
+}
+
+pub struct MdraidCollector;
+
+impl Collector for MdraidCollector {
+    fn name(&self) -> &'static str {
+        "mdraid"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        // /proc/mdstat only exists while the md driver is loaded.
+        let content = fs::read_to_string(PROC_MDSTAT_PATH).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                CollectorError::NotSupported {
+                    path: PROC_MDSTAT_PATH.to_string(),
+                }
+            } else {
+                CollectorError::FileRead {
+                    path: PROC_MDSTAT_PATH.to_string(),
+                    source: e,
+                }
+            }
+        })?;
+        self.collect_from_string(&content)
+    }
+}
+
+impl MdraidCollector {
+    pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
+        let arrays = parse_mdstat(content)?;
+        let device_label = |array: &MdArray| ("device".to_string(), array.device.clone());
+        let gauge = |name: &str, help: &str, samples: Vec<MetricSample>| Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Gauge,
+            samples,
+        };
+        let per_array = |value_fn: &dyn Fn(&MdArray) -> u64| -> Vec<MetricSample> {
+            arrays
+                .iter()
+                .map(|a| MetricSample {
+                    labels: vec![device_label(a)],
+                    value: value_fn(a) as f64,
+                })
+                .collect()
+        };
+        let per_sync = |value_fn: &dyn Fn(&MdSync) -> Option<f64>| -> Vec<MetricSample> {
+            arrays
+                .iter()
+                .filter_map(|a| {
+                    let sync = a.sync.as_ref()?;
+                    Some(MetricSample {
+                        labels: vec![device_label(a), ("action".to_string(), sync.action.clone())],
+                        value: value_fn(sync)?,
+                    })
+                })
+                .collect()
+        };
+
+        Ok(vec![
+            gauge(
+                "sysmetrics_md_info",
+                "RAID level of md arrays.",
+                arrays
+                    .iter()
+                    .map(|a| MetricSample {
+                        labels: vec![device_label(a), ("level".to_string(), a.level.clone())],
+                        value: 1.0,
+                    })
+                    .collect(),
+            ),
+            gauge(
+                "sysmetrics_md_state",
+                "Whether an md array is in the given state.",
+                arrays
+                    .iter()
+                    .flat_map(|a| {
+                        MD_STATES.iter().map(|state| MetricSample {
+                            labels: vec![device_label(a), ("state".to_string(), state.to_string())],
+                            value: if a.state == *state { 1.0 } else { 0.0 },
+                        })
+                    })
+                    .collect(),
+            ),
+            gauge(
+                "sysmetrics_md_disks_required",
+                "Number of disks the md array needs to be complete.",
+                per_array(&|a| a.disks_required),
+            ),
+            gauge(
+                "sysmetrics_md_disks",
+                "Number of md array disks by state.",
+                arrays
+                    .iter()
+                    .flat_map(|a| {
+                        [
+                            ("active", a.disks_active),
+                            ("failed", a.disks_failed),
+                            ("spare", a.disks_spare),
+                            ("replacement", a.disks_replacement),
+                            ("journal", a.disks_journal),
+                        ]
+                        .into_iter()
+                        .map(|(state, count)| MetricSample {
+                            labels: vec![device_label(a), ("state".to_string(), state.to_string())],
+                            value: count as f64,
+                        })
+                    })
+                    .collect(),
+            ),
+            gauge(
+                "sysmetrics_md_sync_progress_ratio",
+                "Progress of a running or queued resync, recovery, reshape or check.",
+                per_sync(&|s| Some(s.progress)),
+            ),
+            gauge(
+                "sysmetrics_md_sync_speed_bytes",
+                "Current speed of a running resync, recovery, reshape or check in bytes per second.",
+                per_sync(&|s| s.speed_bytes),
+            ),
+            gauge(
+                "sysmetrics_md_sync_remaining_seconds",
+                "Estimated time until a running resync, recovery, reshape or check finishes.",
+                per_sync(&|s| s.finish_seconds),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::find_metric;
+
+    const ALL_LEVELS: &str = include_str!("../../tests/fixtures/mdstat/all_levels.txt");
+    const RECOVERING: &str = include_str!("../../tests/fixtures/mdstat/recovering.txt");
+    const RESHAPING: &str = include_str!("../../tests/fixtures/mdstat/reshaping.txt");
+    const INACTIVE: &str = include_str!("../../tests/fixtures/mdstat/inactive.txt");
+    const REPLACING: &str = include_str!("../../tests/fixtures/mdstat/replacing.txt");
+
+    fn array(device: &str, state: &str, level: &str, disks: [u64; 6]) -> MdArray {
+        MdArray {
+            device: device.to_string(),
+            state: state.to_string(),
+            level: level.to_string(),
+            disks_required: disks[0],
+            disks_active: disks[1],
+            disks_failed: disks[2],
+            disks_spare: disks[3],
+            disks_replacement: disks[4],
+            disks_journal: disks[5],
+            sync: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_mdstat_all_levels() {
+        let arrays = parse_mdstat(ALL_LEVELS).unwrap();
+        assert_eq!(
+            arrays,
+            vec![
+                array("md0", "active", "raid1", [2, 2, 0, 0, 0, 0]),
+                // raid0 has no [n/m]; counts come from the member list.
+                array("md1", "active", "raid0", [2, 2, 0, 0, 0, 0]),
+                array("md2", "active", "raid5", [3, 3, 0, 1, 0, 0]),
+                array("md3", "active", "raid6", [4, 4, 0, 0, 0, 0]),
+                array("md4", "active", "raid10", [4, 3, 1, 0, 0, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_mdstat_recovering() {
+        let arrays = parse_mdstat(RECOVERING).unwrap();
+        assert_eq!(arrays.len(), 4);
+        assert_eq!(arrays[0].disks_required, 2);
+        assert_eq!(arrays[0].disks_active, 1);
+        assert_eq!(
+            arrays[0].sync,
+            Some(MdSync {
+                action: "recovery".to_string(),
+                progress: 37043392.0 / 293039104.0,
+                speed_bytes: Some(33440.0 * 1024.0),
+                finish_seconds: Some(127.5 * 60.0),
+            })
+        );
+        assert_eq!(
+            arrays[1].sync,
+            Some(MdSync {
+                action: "resync".to_string(),
+                progress: 0.0,
+                speed_bytes: None,
+                finish_seconds: None,
+            })
+        );
+        assert_eq!(arrays[2].state, "read_only");
+        assert_eq!(arrays[2].level, "raid1");
+        assert_eq!(arrays[2].sync.as_ref().unwrap().action, "resync");
+        assert_eq!(arrays[3].sync.as_ref().unwrap().action, "check");
+    }
+
+    #[test]
+    fn test_parse_mdstat_reshaping() {
+        let arrays = parse_mdstat(RESHAPING).unwrap();
+        let sync = arrays[0].sync.as_ref().unwrap();
+        assert_eq!(sync.action, "reshape");
+        assert_eq!(sync.progress, 4691456.0 / 292837376.0);
+        assert_eq!(sync.speed_bytes, Some(12224.0 * 1024.0));
+    }
+
+    #[test]
+    fn test_parse_mdstat_inactive() {
+        let arrays = parse_mdstat(INACTIVE).unwrap();
+        // Members of an inactive array are listed as spares.
+        assert_eq!(
+            arrays,
+            vec![array("md127", "inactive", "", [0, 0, 0, 2, 0, 0])]
+        );
+    }
+
+    #[test]
+    fn test_parse_mdstat_replacement_and_journal() {
+        let arrays = parse_mdstat(REPLACING).unwrap();
+        let mut md5 = array("md5", "active", "raid5", [3, 3, 0, 0, 1, 1]);
+        md5.sync = arrays[0].sync.clone();
+        assert_eq!(arrays[0], md5);
+        assert_eq!(md5.sync.unwrap().action, "recovery");
+        // Without [n/m], a write-mostly member still counts as required.
+        assert_eq!(
+            arrays[1],
+            array("md6", "active", "linear", [2, 2, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_parse_mdstat_empty_and_malformed() {
+        let empty = "Personalities : \nunused devices: <none>\n";
+        assert!(parse_mdstat(empty).unwrap().is_empty());
+        let bad_state = "md0 : broken raid1 sda1[0]\n";
+        assert!(parse_mdstat(bad_state).is_err());
+        let bad_speed = "md0 : active raid1 sda1[0]\n      [=>...]  resync = 5.0% (5/100) finish=1.0min speed=fastK/sec\n";
+        assert!(parse_mdstat(bad_speed).is_err());
+    }
+
+    #[test]
+    fn test_mdraid_collector_metrics() {
+        let metrics = MdraidCollector.collect_from_string(RECOVERING).unwrap();
+
+        let state = find_metric(&metrics, "sysmetrics_md_state");
+        assert_eq!(state.samples.len(), 4 * MD_STATES.len());
+        let read_only = state
+            .samples
+            .iter()
+            .find(|s| s.labels[0].1 == "md2" && s.labels[1].1 == "read_only")
+            .unwrap();
+        assert_eq!(read_only.value, 1.0);
+
+        let disks = find_metric(&metrics, "sysmetrics_md_disks");
+        assert_eq!(disks.samples.len(), 4 * 5);
+        assert_eq!(disks.samples[0].labels[1].1, "active");
+        assert_eq!(disks.samples[0].value, 1.0);
+
+        let progress = find_metric(&metrics, "sysmetrics_md_sync_progress_ratio");
+        assert_eq!(progress.samples.len(), 4);
+        assert_eq!(
+            progress.samples[0].labels,
+            vec![
+                ("device".to_string(), "md0".to_string()),
+                ("action".to_string(), "recovery".to_string()),
+            ]
+        );
+        // Delayed and pending syncs have no speed.
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_md_sync_speed_bytes")
+                .samples
+                .len(),
+            2
+        );
+    }
+}
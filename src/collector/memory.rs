@@ -5,27 +5,73 @@ use std::fs;
 
 const PROC_MEMINFO_PATH: &str = "/proc/meminfo";
 
-/// Parse /proc/meminfo content into a map of field name -> value in kB.
-pub fn parse_meminfo(content: &str) -> Result<HashMap<String, u64>, CollectorError> {
-    let mut map = HashMap::new();
+/// /proc/meminfo keys exported as fixed metrics: key, metric name, help.
+const FIXED_FIELDS: &[(&str, &str, &str)] = &[
+    (
+        "MemTotal",
+        "sysmetrics_memory_total_bytes",
+        "Total memory in bytes.",
+    ),
+    (
+        "MemFree",
+        "sysmetrics_memory_free_bytes",
+        "Free memory in bytes.",
+    ),
+    (
+        "MemAvailable",
+        "sysmetrics_memory_available_bytes",
+        "Available memory in bytes.",
+    ),
+    (
+        "Buffers",
+        "sysmetrics_memory_buffers_bytes",
+        "Buffer memory in bytes.",
+    ),
+    (
+        "Cached",
+        "sysmetrics_memory_cached_bytes",
+        "Cached memory in bytes.",
+    ),
+    (
+        "SwapTotal",
+        "sysmetrics_memory_swap_total_bytes",
+        "Total swap in bytes.",
+    ),
+    (
+        "SwapFree",
+        "sysmetrics_memory_swap_free_bytes",
+        "Free swap in bytes.",
+    ),
+];
+
+/// A single /proc/meminfo line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeminfoEntry {
+    pub key: String,
+    pub value: u64,
+    /// Whether the value carries a `kB` unit. Fields such as
+    /// `HugePages_Total` are plain counts.
+    pub in_kb: bool,
+}
+
+/// Parse /proc/meminfo content into entries, preserving file order.
+pub fn parse_meminfo_entries(content: &str) -> Result<Vec<MeminfoEntry>, CollectorError> {
+    let mut entries = Vec::new();
     for line in content.lines() {
         let line = line.trim();
         if line.is_empty() {
             continue;
         }
-        // Format: "FieldName:     12345 kB"
+        // Format: "FieldName:     12345 kB" or "HugePages_Total:       0"
         let Some((key, rest)) = line.split_once(':') else {
             continue;
         };
-        let rest = rest.trim();
-        let value_str = rest
-            .split_whitespace()
-            .next()
-            .ok_or_else(|| CollectorError::Parse {
-                path: PROC_MEMINFO_PATH.to_string(),
-                field: key.to_string(),
-                raw: line.to_string(),
-            })?;
+        let mut parts = rest.split_whitespace();
+        let value_str = parts.next().ok_or_else(|| CollectorError::Parse {
+            path: PROC_MEMINFO_PATH.to_string(),
+            field: key.to_string(),
+            raw: line.to_string(),
+        })?;
         let value = value_str
             .parse::<u64>()
             .map_err(|_| CollectorError::Parse {
@@ -33,12 +79,52 @@ pub fn parse_meminfo(content: &str) -> Result<HashMap<String, u64>, CollectorErr
                 field: key.to_string(),
                 raw: value_str.to_string(),
             })?;
-        map.insert(key.to_string(), value);
+        entries.push(MeminfoEntry {
+            key: key.to_string(),
+            value,
+            in_kb: parts.next() == Some("kB"),
+        });
     }
-    Ok(map)
+    Ok(entries)
 }
 
-fn get_field(map: &HashMap<String, u64>, field: &str) -> Result<u64, CollectorError> {
+/// Map of field name -> value in kB (or a plain count for unitless
+/// fields) of parsed /proc/meminfo entries.
+pub fn meminfo_map(entries: &[MeminfoEntry]) -> HashMap<&str, u64> {
+    entries.iter().map(|e| (e.key.as_str(), e.value)).collect()
+}
+
+/// Convert a /proc/meminfo key to a snake_case metric name fragment,
+/// e.g. `HugePages_Total` -> `huge_pages_total`, `Active(anon)` ->
+/// `active_anon`, `SUnreclaim` -> `s_unreclaim`, `DirectMap2M` -> `direct_map_2m`.
+pub fn meminfo_key_to_snake_case(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let mut out = String::with_capacity(key.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            continue;
+        }
+        if i > 0 && !out.is_empty() && !out.ends_with('_') {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+            let boundary = (c.is_ascii_uppercase() && prev.is_ascii_lowercase())
+                || (c.is_ascii_uppercase()
+                    && prev.is_ascii_uppercase()
+                    && next.is_some_and(|n| n.is_ascii_lowercase()))
+                || (c.is_ascii_digit() && prev.is_ascii_alphabetic());
+            if boundary {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+fn get_field(map: &HashMap<&str, u64>, field: &str) -> Result<u64, CollectorError> {
     map.get(field)
         .copied()
         .ok_or_else(|| CollectorError::Parse {
@@ -48,7 +134,17 @@ fn get_field(map: &HashMap<String, u64>, field: &str) -> Result<u64, CollectorEr
         })
 }
 
-pub struct MemoryCollector;
+pub struct MemoryCollector {
+    all_fields: bool,
+}
+
+impl MemoryCollector {
+    /// With `all_fields`, every /proc/meminfo field is exported in addition
+    /// to the fixed set of memory metrics.
+    pub fn new(all_fields: bool) -> Self {
+        Self { all_fields }
+    }
+}
 
 impl Collector for MemoryCollector {
     fn name(&self) -> &'static str {
@@ -67,78 +163,91 @@ impl Collector for MemoryCollector {
 
 impl MemoryCollector {
     pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
-        let map = parse_meminfo(content)?;
+        let entries = parse_meminfo_entries(content)?;
+        let map = meminfo_map(&entries);
 
         let total = get_field(&map, "MemTotal")?;
         let free = get_field(&map, "MemFree")?;
-        let available = get_field(&map, "MemAvailable")?;
-        let buffers = get_field(&map, "Buffers")?;
-        let cached = get_field(&map, "Cached")?;
-        let swap_total = get_field(&map, "SwapTotal")?;
-        let swap_free = get_field(&map, "SwapFree")?;
+        // Fields that may be absent (e.g. MemAvailable before Linux 3.14)
+        // are skipped rather than failing the collector.
+        let optional = |field: &str| map.get(field).copied();
 
         let used = total
             .saturating_sub(free)
-            .saturating_sub(buffers)
-            .saturating_sub(cached);
+            .saturating_sub(optional("Buffers").unwrap_or(0))
+            .saturating_sub(optional("Cached").unwrap_or(0));
 
         let kb_to_bytes = 1024.0;
+        let gauge = |name: &str, help: &str, value_kb: u64| Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Gauge,
+            samples: vec![MetricSample {
+                labels: vec![],
+                value: value_kb as f64 * kb_to_bytes,
+            }],
+        };
 
-        let metrics = vec![
-            (
-                "sysmetrics_memory_total_bytes",
-                "Total memory in bytes.",
-                total,
-            ),
-            (
-                "sysmetrics_memory_free_bytes",
-                "Free memory in bytes.",
-                free,
-            ),
-            (
-                "sysmetrics_memory_available_bytes",
-                "Available memory in bytes.",
-                available,
-            ),
-            (
-                "sysmetrics_memory_buffers_bytes",
-                "Buffer memory in bytes.",
-                buffers,
-            ),
-            (
-                "sysmetrics_memory_cached_bytes",
-                "Cached memory in bytes.",
-                cached,
-            ),
-            (
-                "sysmetrics_memory_swap_total_bytes",
-                "Total swap in bytes.",
-                swap_total,
-            ),
-            (
-                "sysmetrics_memory_swap_free_bytes",
-                "Free swap in bytes.",
-                swap_free,
-            ),
-            (
-                "sysmetrics_memory_used_bytes",
-                "Used memory in bytes (total - free - buffers - cached).",
-                used,
-            ),
-        ];
-
-        Ok(metrics
-            .into_iter()
-            .map(|(name, help, value_kb)| Metric {
-                name: name.to_string(),
-                help: help.to_string(),
-                metric_type: MetricType::Gauge,
-                samples: vec![MetricSample {
-                    labels: vec![],
-                    value: value_kb as f64 * kb_to_bytes,
-                }],
-            })
-            .collect())
+        let mut metrics: Vec<Metric> = FIXED_FIELDS
+            .iter()
+            .filter_map(|(key, name, help)| Some(gauge(name, help, optional(key)?)))
+            .collect();
+        metrics.push(gauge(
+            "sysmetrics_memory_used_bytes",
+            "Used memory in bytes (total - free - buffers - cached).",
+            used,
+        ));
+
+        if self.all_fields {
+            // Metric name -> meminfo key it was generated from.
+            let mut sources: HashMap<String, String> = HashMap::new();
+            for entry in &entries {
+                // Keys behind the fixed metrics are already exported.
+                if FIXED_FIELDS.iter().any(|(key, ..)| *key == entry.key) {
+                    continue;
+                }
+                let key = meminfo_key_to_snake_case(&entry.key);
+                let (name, value) = if entry.in_kb {
+                    (
+                        format!("sysmetrics_memory_{}_bytes", key),
+                        entry.value as f64 * kb_to_bytes,
+                    )
+                } else {
+                    // These are gauges, so `HugePages_Total` must not end in
+                    // the counter suffix `_total`.
+                    let key = match key.strip_suffix("_total") {
+                        Some(base) => format!("{}_count", base),
+                        None => key,
+                    };
+                    (format!("sysmetrics_memory_{}", key), entry.value as f64)
+                };
+                if let Some(previous) = sources.get(&name) {
+                    tracing::warn!(
+                        metric = %name,
+                        key = %entry.key,
+                        previous_key = %previous,
+                        "/proc/meminfo keys map to the same metric name, skipping"
+                    );
+                    continue;
+                }
+                sources.insert(name.clone(), entry.key.clone());
+                // Guard against a generated name matching a fixed one.
+                if metrics.iter().any(|m| m.name == name) {
+                    continue;
+                }
+                metrics.push(Metric {
+                    name,
+                    help: format!("/proc/meminfo field {}.", entry.key),
+                    metric_type: MetricType::Gauge,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value,
+                    }],
+                });
+            }
+        }
+
+        Ok(metrics)
     }
 }
 
@@ -159,7 +268,8 @@ SwapFree:        4194000 kB
 
     #[test]
     fn test_parse_meminfo() {
-        let map = parse_meminfo(MEMINFO_FIXTURE).unwrap();
+        let entries = parse_meminfo_entries(MEMINFO_FIXTURE).unwrap();
+        let map = meminfo_map(&entries);
         assert_eq!(map["MemTotal"], 16384000);
         assert_eq!(map["MemFree"], 1234567);
         assert_eq!(map["MemAvailable"], 8765432);
@@ -171,20 +281,20 @@ SwapFree:        4194000 kB
 
     #[test]
     fn test_parse_meminfo_empty() {
-        let map = parse_meminfo("").unwrap();
-        assert!(map.is_empty());
+        let entries = parse_meminfo_entries("").unwrap();
+        assert!(meminfo_map(&entries).is_empty());
     }
 
     #[test]
     fn test_parse_meminfo_malformed_value() {
         let input = "MemTotal:       abc kB\n";
-        let result = parse_meminfo(input);
+        let result = parse_meminfo_entries(input);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_memory_collector_metrics() {
-        let collector = MemoryCollector;
+        let collector = MemoryCollector::new(false);
         let metrics = collector.collect_from_string(MEMINFO_FIXTURE).unwrap();
         assert_eq!(metrics.len(), 8);
 
@@ -202,10 +312,113 @@ SwapFree:        4194000 kB
     }
 
     #[test]
-    fn test_memory_collector_missing_field() {
-        let input = "MemTotal: 16384000 kB\nMemFree: 1234567 kB\n";
-        let collector = MemoryCollector;
+    fn test_memory_collector_missing_required_field() {
+        let input = "MemTotal: 16384000 kB\nMemAvailable: 8765432 kB\n";
+        let collector = MemoryCollector::new(false);
         let result = collector.collect_from_string(input);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_memory_collector_missing_optional_field() {
+        // Pre-3.14 kernels have no MemAvailable, containers may lack swap.
+        let input = "MemTotal: 16384000 kB\nMemFree: 1234567 kB\nCached: 1000 kB\n";
+        let collector = MemoryCollector::new(false);
+        let metrics = collector.collect_from_string(input).unwrap();
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "sysmetrics_memory_total_bytes",
+                "sysmetrics_memory_free_bytes",
+                "sysmetrics_memory_cached_bytes",
+                "sysmetrics_memory_used_bytes",
+            ]
+        );
+        let expected_used = (16384000u64 - 1234567 - 1000) as f64 * 1024.0;
+        assert_eq!(metrics[3].samples[0].value, expected_used);
+    }
+
+    #[test]
+    fn test_parse_meminfo_entries_units() {
+        let input = "MemTotal: 16384000 kB\nHugePages_Total:       4\n";
+        let entries = parse_meminfo_entries(input).unwrap();
+        assert!(entries[0].in_kb);
+        assert_eq!(entries[1].key, "HugePages_Total");
+        assert_eq!(entries[1].value, 4);
+        assert!(!entries[1].in_kb);
+    }
+
+    #[test]
+    fn test_meminfo_key_to_snake_case() {
+        assert_eq!(meminfo_key_to_snake_case("MemTotal"), "mem_total");
+        assert_eq!(
+            meminfo_key_to_snake_case("HugePages_Total"),
+            "huge_pages_total"
+        );
+        assert_eq!(meminfo_key_to_snake_case("Active(anon)"), "active_anon");
+        assert_eq!(meminfo_key_to_snake_case("SUnreclaim"), "s_unreclaim");
+        assert_eq!(meminfo_key_to_snake_case("KReclaimable"), "k_reclaimable");
+        assert_eq!(meminfo_key_to_snake_case("NFS_Unstable"), "nfs_unstable");
+        assert_eq!(meminfo_key_to_snake_case("Committed_AS"), "committed_as");
+        assert_eq!(meminfo_key_to_snake_case("DirectMap2M"), "direct_map_2m");
+        assert_eq!(meminfo_key_to_snake_case("Hugepagesize"), "hugepagesize");
+    }
+
+    #[test]
+    fn test_memory_collector_all_fields() {
+        let input = format!(
+            "{}Active(anon):      45353 kB\nHugePages_Total:       4\n",
+            MEMINFO_FIXTURE
+        );
+        let collector = MemoryCollector::new(true);
+        let metrics = collector.collect_from_string(&input).unwrap();
+
+        let find = |name: &str| metrics.iter().find(|m| m.name == name);
+        // Keys behind a fixed metric are not exported a second time.
+        assert!(find("sysmetrics_memory_mem_total_bytes").is_none());
+        assert!(find("sysmetrics_memory_mem_free_bytes").is_none());
+        assert!(find("sysmetrics_memory_mem_available_bytes").is_none());
+        assert_eq!(metrics.len(), 8 + 3);
+        assert_eq!(
+            find("sysmetrics_memory_swap_cached_bytes").unwrap().samples[0].value,
+            12345.0 * 1024.0
+        );
+        assert_eq!(
+            find("sysmetrics_memory_active_anon_bytes").unwrap().samples[0].value,
+            45353.0 * 1024.0
+        );
+        // Unitless fields are counts, not bytes.
+        assert_eq!(
+            find("sysmetrics_memory_huge_pages_count").unwrap().samples[0].value,
+            4.0
+        );
+        // Gauges never carry the counter suffix.
+        assert!(find("sysmetrics_memory_huge_pages_total").is_none());
+
+        // Keys that map onto a fixed metric are not duplicated.
+        let mut names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn test_memory_collector_all_fields_name_collision() {
+        // Both keys become `active_anon`; the first one wins.
+        let input = format!(
+            "{}Active(anon):      45353 kB\nActive_anon:       1 kB\n",
+            MEMINFO_FIXTURE
+        );
+        let metrics = MemoryCollector::new(true)
+            .collect_from_string(&input)
+            .unwrap();
+        let active: Vec<&Metric> = metrics
+            .iter()
+            .filter(|m| m.name == "sysmetrics_memory_active_anon_bytes")
+            .collect();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].samples[0].value, 45353.0 * 1024.0);
+    }
 }
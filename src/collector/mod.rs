@@ -1,10 +1,31 @@
+pub mod btrfs;
+pub mod cgroup;
 pub mod cpu;
+pub mod cpufreq;
 pub mod disk;
+pub mod filesystem;
+pub mod hwmon;
+pub mod loadavg;
+pub mod mdraid;
 pub mod memory;
+pub mod netstat;
 pub mod network;
+pub mod pressure;
+pub mod process_states;
+pub mod processes;
+pub mod self_process;
+pub mod tcpstat;
+pub mod thermal;
+pub mod vmstat;
+pub mod zfs;
 
 use crate::error::CollectorError;
+use std::collections::HashMap;
 use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Type of a Prometheus metric.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -89,6 +110,89 @@ pub struct CollectorResult {
     pub result: Result<Vec<Metric>, CollectorError>,
 }
 
+/// Read a sysfs attribute with surrounding whitespace trimmed. Returns `None`
+/// if the file is missing or unreadable, which is normal for optional
+/// attributes and for sensors whose driver returns an error on read.
+pub fn read_sysfs_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Read and parse a single-value sysfs attribute.
+pub fn read_sysfs_value<T: FromStr>(path: &Path) -> Option<T> {
+    read_sysfs_string(path)?.parse().ok()
+}
+
+/// List the entries of a sysfs directory whose name starts with `prefix`,
+/// sorted by name with numeric suffixes compared numerically (so `hwmon10`
+/// sorts after `hwmon2`). A missing directory is reported as `NotSupported`.
+pub fn list_sysfs_dir(dir: &Path, prefix: &str) -> Result<Vec<PathBuf>, CollectorError> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            CollectorError::NotSupported {
+                path: dir.display().to_string(),
+            }
+        } else {
+            CollectorError::FileRead {
+                path: dir.display().to_string(),
+                source: e,
+            }
+        }
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(prefix))
+        })
+        .collect();
+    paths.sort_by_key(|p| {
+        let name = p
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let suffix = name[prefix.len()..].parse::<u64>().ok();
+        (suffix, name)
+    });
+    Ok(paths)
+}
+
+/// Read a file that may not exist. `Ok(None)` if it is missing, e.g. the
+/// IPv6 files under /proc/net when IPv6 is disabled.
+pub fn read_optional(path: &Path) -> Result<Option<String>, CollectorError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(CollectorError::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        }),
+    }
+}
+
+/// Parse a flat keyed file (`key value` per line) such as cgroup
+/// `cpu.stat` or btrfs `error_stats`.
+pub fn parse_flat_keyed(content: &str, path: &str) -> Result<HashMap<String, u64>, CollectorError> {
+    let mut fields = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parse_err = || CollectorError::Parse {
+            path: path.to_string(),
+            field: "keyed line".to_string(),
+            raw: line.to_string(),
+        };
+        let (key, raw) = line.split_once(' ').ok_or_else(parse_err)?;
+        let value = raw.trim().parse::<u64>().map_err(|_| parse_err())?;
+        fields.insert(key.to_string(), value);
+    }
+    Ok(fields)
+}
+
 /// Render a slice of metrics into Prometheus exposition format.
 pub fn render_metrics(metrics: &[Metric]) -> String {
     let mut output = String::new();
@@ -151,10 +255,41 @@ fn format_float(v: f64) -> String {
     }
 }
 
+/// Find a metric family by name in test output.
+#[cfg(test)]
+pub(crate) fn find_metric<'a>(metrics: &'a [Metric], name: &str) -> &'a Metric {
+    metrics
+        .iter()
+        .find(|m| m.name == name)
+        .unwrap_or_else(|| panic!("metric {} not found", name))
+}
+
+/// Assert that `collect` reports a root directory that does not exist as
+/// `NotSupported`.
+#[cfg(test)]
+pub(crate) fn assert_missing_root_not_supported(
+    collect: impl Fn(&Path) -> Result<Vec<Metric>, CollectorError>,
+) {
+    let result = collect(Path::new("/nonexistent/sysmetrics"));
+    assert!(
+        matches!(result, Err(CollectorError::NotSupported { .. })),
+        "expected NotSupported, got {:?}",
+        result.map(|metrics| metrics.len())
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_flat_keyed() {
+        let fields = parse_flat_keyed("usage_usec 100\nnr_throttled 3\n", "cpu.stat").unwrap();
+        assert_eq!(fields["usage_usec"], 100);
+        assert_eq!(fields["nr_throttled"], 3);
+        assert!(parse_flat_keyed("usage_usec abc\n", "cpu.stat").is_err());
+    }
+
     #[test]
     fn test_escape_label_value_plain() {
         assert_eq!(escape_label_value("hello"), "hello");
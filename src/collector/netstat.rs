@@ -0,0 +1,381 @@
+use crate::collector::{read_optional, Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use regex::Regex;
+use std::path::Path;
+
+const PROC_NET_PATH: &str = "/proc/net";
+
+/// Protocol statistics that report a current value or a setting rather than
+/// a cumulative count.
+const GAUGE_FIELDS: &[&str] = &[
+    "Ip_Forwarding",
+    "Ip_DefaultTTL",
+    "Tcp_RtoAlgorithm",
+    "Tcp_RtoMin",
+    "Tcp_RtoMax",
+    "Tcp_MaxConn",
+    "Tcp_CurrEstab",
+];
+
+/// A single protocol statistic, e.g. protocol `Tcp`, field `RetransSegs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolStat {
+    pub protocol: String,
+    pub field: String,
+    pub value: f64,
+}
+
+impl ProtocolStat {
+    /// `Protocol_Field`, the key matched by the field allowlist.
+    pub fn key(&self) -> String {
+        format!("{}_{}", self.protocol, self.field)
+    }
+}
+
+/// Parse the paired header/value line format of /proc/net/snmp and
+/// /proc/net/netstat:
+///
+/// ```text
+/// Tcp: RtoAlgorithm RtoMin ...
+/// Tcp: 1 200 ...
+/// ```
+pub fn parse_paired_stats(content: &str, path: &str) -> Result<Vec<ProtocolStat>, CollectorError> {
+    let mut stats = Vec::new();
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    while let Some(header) = lines.next() {
+        let parse_err = |field: &str, raw: &str| CollectorError::Parse {
+            path: path.to_string(),
+            field: field.to_string(),
+            raw: raw.to_string(),
+        };
+        let values = lines
+            .next()
+            .ok_or_else(|| parse_err("value line", header))?;
+        let (protocol, names) = header
+            .split_once(':')
+            .ok_or_else(|| parse_err("header line", header))?;
+        let (value_protocol, raw_values) = values
+            .split_once(':')
+            .ok_or_else(|| parse_err("value line", values))?;
+        let names: Vec<&str> = names.split_whitespace().collect();
+        let raw_values: Vec<&str> = raw_values.split_whitespace().collect();
+        if protocol != value_protocol || names.len() != raw_values.len() {
+            return Err(parse_err(protocol, values));
+        }
+        for (name, raw) in names.iter().zip(raw_values) {
+            let value = raw
+                .parse::<i64>()
+                .map_err(|_| parse_err(&format!("{}_{}", protocol, name), raw))?;
+            stats.push(ProtocolStat {
+                protocol: protocol.to_string(),
+                field: name.to_string(),
+                value: value as f64,
+            });
+        }
+    }
+    Ok(stats)
+}
+
+/// Parse /proc/net/snmp6, one `Ip6InReceives  123` pair per line. The
+/// protocol is the key up to and including the `6`.
+pub fn parse_snmp6(content: &str, path: &str) -> Result<Vec<ProtocolStat>, CollectorError> {
+    let mut stats = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(raw)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let parse_err = || CollectorError::Parse {
+            path: path.to_string(),
+            field: key.to_string(),
+            raw: line.to_string(),
+        };
+        let split = key.find('6').ok_or_else(parse_err)? + 1;
+        let value = raw.parse::<i64>().map_err(|_| parse_err())?;
+        stats.push(ProtocolStat {
+            protocol: key[..split].to_string(),
+            field: key[split..].to_string(),
+            value: value as f64,
+        });
+    }
+    Ok(stats)
+}
+
+/// Parse /proc/net/sockstat or sockstat6 into (protocol, key, value), e.g.
+/// (`TCP`, `inuse`, 55).
+pub fn parse_sockstat(
+    content: &str,
+    path: &str,
+) -> Result<Vec<(String, String, u64)>, CollectorError> {
+    let mut stats = Vec::new();
+    for line in content.lines() {
+        let Some((protocol, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        for pair in parts.chunks(2) {
+            let [key, raw] = pair else {
+                return Err(CollectorError::Parse {
+                    path: path.to_string(),
+                    field: protocol.to_string(),
+                    raw: line.to_string(),
+                });
+            };
+            let value = raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+                path: path.to_string(),
+                field: format!("{}_{}", protocol, key),
+                raw: raw.to_string(),
+            })?;
+            stats.push((protocol.to_string(), key.to_string(), value));
+        }
+    }
+    Ok(stats)
+}
+
+fn page_size() -> u64 {
+    // SAFETY: sysconf has no preconditions.
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 {
+        size as u64
+    } else {
+        4096
+    }
+}
+
+pub struct NetstatCollector {
+    field_include_pattern: Regex,
+}
+
+impl NetstatCollector {
+    pub fn new(field_include_pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            field_include_pattern: Regex::new(field_include_pattern)?,
+        })
+    }
+}
+
+impl Collector for NetstatCollector {
+    fn name(&self) -> &'static str {
+        "netstat"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(PROC_NET_PATH), page_size())
+    }
+}
+
+impl NetstatCollector {
+    /// `page_size` converts the `mem` fields of sockstat, which are in
+    /// pages, to bytes.
+    pub fn collect_from_dir(
+        &self,
+        root: &Path,
+        page_size: u64,
+    ) -> Result<Vec<Metric>, CollectorError> {
+        // /proc/net/snmp exists whenever the kernel has IPv4 networking.
+        if !root.join("snmp").exists() {
+            return Err(CollectorError::NotSupported {
+                path: root.join("snmp").display().to_string(),
+            });
+        }
+        let mut stats = Vec::new();
+        for file in ["snmp", "netstat"] {
+            let path = root.join(file);
+            if let Some(content) = read_optional(&path)? {
+                stats.extend(parse_paired_stats(&content, &path.display().to_string())?);
+            }
+        }
+        let snmp6_path = root.join("snmp6");
+        if let Some(content) = read_optional(&snmp6_path)? {
+            stats.extend(parse_snmp6(&content, &snmp6_path.display().to_string())?);
+        }
+
+        let mut metrics: Vec<Metric> = stats
+            .iter()
+            .filter(|s| self.field_include_pattern.is_match(&s.key()))
+            .map(|s| {
+                let key = s.key();
+                let (name, metric_type) = if GAUGE_FIELDS.contains(&key.as_str()) {
+                    (format!("sysmetrics_netstat_{}", key), MetricType::Gauge)
+                } else {
+                    (
+                        format!("sysmetrics_netstat_{}_total", key),
+                        MetricType::Counter,
+                    )
+                };
+                Metric {
+                    name,
+                    help: format!("Protocol statistic {} {}.", s.protocol, s.field),
+                    metric_type,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value: s.value,
+                    }],
+                }
+            })
+            .collect();
+
+        for file in ["sockstat", "sockstat6"] {
+            let path = root.join(file);
+            let Some(content) = read_optional(&path)? else {
+                continue;
+            };
+            for (protocol, key, value) in parse_sockstat(&content, &path.display().to_string())? {
+                if !self
+                    .field_include_pattern
+                    .is_match(&format!("{}_{}", protocol, key))
+                {
+                    continue;
+                }
+                // `mem` is in pages, the FRAG `memory` already in bytes.
+                let (name, value) = if key == "mem" {
+                    (
+                        format!("sysmetrics_sockstat_{}_mem_bytes", protocol),
+                        (value * page_size) as f64,
+                    )
+                } else if key == "memory" {
+                    (
+                        format!("sysmetrics_sockstat_{}_memory_bytes", protocol),
+                        value as f64,
+                    )
+                } else {
+                    (
+                        format!("sysmetrics_sockstat_{}_{}", protocol, key),
+                        value as f64,
+                    )
+                };
+                metrics.push(Metric {
+                    name,
+                    help: format!("Socket statistic {} {}.", protocol, key),
+                    metric_type: MetricType::Gauge,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value,
+                    }],
+                });
+            }
+        }
+
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const NET_FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc/net");
+
+    #[test]
+    fn test_parse_paired_stats() {
+        let content = "\
+Tcp: RtoAlgorithm MaxConn RetransSegs
+Tcp: 1 -1 17
+Udp: InDatagrams NoPorts
+Udp: 6 2
+";
+        let stats = parse_paired_stats(content, "/proc/net/snmp").unwrap();
+        assert_eq!(stats.len(), 5);
+        assert_eq!(stats[1].key(), "Tcp_MaxConn");
+        assert_eq!(stats[1].value, -1.0);
+        assert_eq!(stats[2].value, 17.0);
+        assert_eq!(stats[4].key(), "Udp_NoPorts");
+    }
+
+    #[test]
+    fn test_parse_paired_stats_mismatch() {
+        assert!(parse_paired_stats("Tcp: A B\nTcp: 1\n", "snmp").is_err());
+        assert!(parse_paired_stats("Tcp: A\nUdp: 1\n", "snmp").is_err());
+        assert!(parse_paired_stats("Tcp: A\n", "snmp").is_err());
+        assert!(parse_paired_stats("Tcp: A\nTcp: x\n", "snmp").is_err());
+    }
+
+    #[test]
+    fn test_parse_snmp6() {
+        let content = "Ip6InReceives   \t3\nIcmp6InMsgs  5\nUdpLite6InErrors 1\n";
+        let stats = parse_snmp6(content, "/proc/net/snmp6").unwrap();
+        assert_eq!(stats[0].key(), "Ip6_InReceives");
+        assert_eq!(stats[1].key(), "Icmp6_InMsgs");
+        assert_eq!(stats[2].key(), "UdpLite6_InErrors");
+        assert_eq!(stats[1].value, 5.0);
+    }
+
+    #[test]
+    fn test_parse_sockstat() {
+        let content = "sockets: used 195\nTCP: inuse 55 orphan 1 tw 6 alloc 58 mem 3\n";
+        let stats = parse_sockstat(content, "/proc/net/sockstat").unwrap();
+        assert_eq!(stats.len(), 6);
+        assert_eq!(stats[0], ("sockets".to_string(), "used".to_string(), 195));
+        assert_eq!(stats[5], ("TCP".to_string(), "mem".to_string(), 3));
+        assert!(parse_sockstat("TCP: inuse\n", "sockstat").is_err());
+    }
+
+    #[test]
+    fn test_netstat_collector_allowlist() {
+        let collector = NetstatCollector::new(
+            "^(Tcp_(RetransSegs|CurrEstab)|TcpExt_Listen.*|Udp6_InDatagrams)$",
+        )
+        .unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(NET_FIXTURE_DIR), 4096)
+            .unwrap();
+
+        let retrans = find_metric(&metrics, "sysmetrics_netstat_Tcp_RetransSegs_total");
+        assert_eq!(retrans.metric_type, MetricType::Counter);
+        assert_eq!(retrans.samples[0].value, 17.0);
+
+        let estab = find_metric(&metrics, "sysmetrics_netstat_Tcp_CurrEstab");
+        assert_eq!(estab.metric_type, MetricType::Gauge);
+        assert_eq!(estab.samples[0].value, 2.0);
+
+        let overflows = find_metric(&metrics, "sysmetrics_netstat_TcpExt_ListenOverflows_total");
+        assert_eq!(overflows.samples[0].value, 5.0);
+
+        let udp6 = find_metric(&metrics, "sysmetrics_netstat_Udp6_InDatagrams_total");
+        assert_eq!(udp6.samples[0].value, 42.0);
+
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name.starts_with("sysmetrics_netstat_Ip_")));
+    }
+
+    #[test]
+    fn test_netstat_collector_sockstat() {
+        let collector =
+            NetstatCollector::new("^(sockets_used|TCP_(tw|mem)|TCP6_inuse|FRAG_memory)$").unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(NET_FIXTURE_DIR), 4096)
+            .unwrap();
+        // Sockstat rows go through the same allowlist.
+        assert_eq!(metrics.len(), 5);
+
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_sockstat_sockets_used").samples[0].value,
+            195.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_sockstat_TCP_tw").samples[0].value,
+            6.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_sockstat_TCP_mem_bytes").samples[0].value,
+            3.0 * 4096.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_sockstat_TCP6_inuse").samples[0].value,
+            7.0
+        );
+        // FRAG memory is already in bytes.
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_sockstat_FRAG_memory_bytes").samples[0].value,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_netstat_collector_missing_root() {
+        let collector = NetstatCollector::new(".*").unwrap();
+        assert_missing_root_not_supported(|root| collector.collect_from_dir(root, 4096));
+    }
+}
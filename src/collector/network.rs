@@ -1,9 +1,15 @@
-use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::collector::{
+    list_sysfs_dir, read_sysfs_string, read_sysfs_value, Collector, Metric, MetricSample,
+    MetricType,
+};
 use crate::error::CollectorError;
 use regex::Regex;
 use std::fs;
+use std::path::Path;
 
 const PROC_NET_DEV_PATH: &str = "/proc/net/dev";
+const SYS_CLASS_NET_PATH: &str = "/sys/class/net";
+const BYTES_PER_MEGABIT: f64 = 1_000_000.0 / 8.0;
 
 /// Parsed network interface statistics.
 #[derive(Debug, Clone)]
@@ -13,10 +19,18 @@ pub struct NetStats {
     pub rx_packets: u64,
     pub rx_errors: u64,
     pub rx_drop: u64,
+    pub rx_fifo: u64,
+    pub rx_frame: u64,
+    pub rx_compressed: u64,
+    pub rx_multicast: u64,
     pub tx_bytes: u64,
     pub tx_packets: u64,
     pub tx_errors: u64,
     pub tx_drop: u64,
+    pub tx_fifo: u64,
+    pub tx_colls: u64,
+    pub tx_carrier: u64,
+    pub tx_compressed: u64,
 }
 
 /// Parse /proc/net/dev content into per-interface statistics.
@@ -56,16 +70,58 @@ pub fn parse_net_dev(content: &str) -> Result<Vec<NetStats>, CollectorError> {
             rx_packets: parse_field(1, "rx_packets", &interface)?,
             rx_errors: parse_field(2, "rx_errors", &interface)?,
             rx_drop: parse_field(3, "rx_drop", &interface)?,
+            rx_fifo: parse_field(4, "rx_fifo", &interface)?,
+            rx_frame: parse_field(5, "rx_frame", &interface)?,
+            rx_compressed: parse_field(6, "rx_compressed", &interface)?,
+            rx_multicast: parse_field(7, "rx_multicast", &interface)?,
             tx_bytes: parse_field(8, "tx_bytes", &interface)?,
             tx_packets: parse_field(9, "tx_packets", &interface)?,
             tx_errors: parse_field(10, "tx_errors", &interface)?,
             tx_drop: parse_field(11, "tx_drop", &interface)?,
+            tx_fifo: parse_field(12, "tx_fifo", &interface)?,
+            tx_colls: parse_field(13, "tx_colls", &interface)?,
+            tx_carrier: parse_field(14, "tx_carrier", &interface)?,
+            tx_compressed: parse_field(15, "tx_compressed", &interface)?,
             interface,
         });
     }
     Ok(stats)
 }
 
+/// Link properties of an interface from /sys/class/net/<iface>/.
+///
+/// Several attributes fail to read (EINVAL) while the link is down or for
+/// virtual devices; those are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetDevProperties {
+    pub interface: String,
+    /// Link speed in Mbit/s.
+    pub speed: Option<i64>,
+    pub mtu: Option<u64>,
+    pub carrier: Option<u64>,
+    pub carrier_changes: Option<u64>,
+    pub operstate: Option<String>,
+    pub duplex: Option<String>,
+    pub address: Option<String>,
+    /// ARPHRD_* hardware type, e.g. 1 for Ethernet.
+    pub device_type: Option<String>,
+}
+
+/// Read the link properties of a single interface directory.
+pub fn read_net_dev_properties(dir: &Path, interface: &str) -> NetDevProperties {
+    NetDevProperties {
+        interface: interface.to_string(),
+        speed: read_sysfs_value(&dir.join("speed")),
+        mtu: read_sysfs_value(&dir.join("mtu")),
+        carrier: read_sysfs_value(&dir.join("carrier")),
+        carrier_changes: read_sysfs_value(&dir.join("carrier_changes")),
+        operstate: read_sysfs_string(&dir.join("operstate")),
+        duplex: read_sysfs_string(&dir.join("duplex")),
+        address: read_sysfs_string(&dir.join("address")),
+        device_type: read_sysfs_string(&dir.join("type")),
+    }
+}
+
 pub struct NetworkCollector {
     exclude_pattern: Regex,
 }
@@ -89,11 +145,33 @@ impl Collector for NetworkCollector {
                 path: PROC_NET_DEV_PATH.to_string(),
                 source: e,
             })?;
-        self.collect_from_string(&content)
+        self.collect_with_sysfs(&content, Path::new(SYS_CLASS_NET_PATH))
     }
 }
 
 impl NetworkCollector {
+    /// /proc/net/dev metrics from `content`, plus link properties from
+    /// `sysfs_root` where it can be read.
+    pub fn collect_with_sysfs(
+        &self,
+        content: &str,
+        sysfs_root: &Path,
+    ) -> Result<Vec<Metric>, CollectorError> {
+        let mut metrics = self.collect_from_string(content)?;
+        // /sys may not be mounted or may be unreadable in a container;
+        // /proc/net/dev metrics are still reported.
+        match self.collect_from_sysfs(sysfs_root) {
+            Ok(sysfs_metrics) => metrics.extend(sysfs_metrics),
+            Err(CollectorError::NotSupported { .. }) => {
+                tracing::debug!(path = %sysfs_root.display(), "no sysfs network devices");
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to read sysfs network devices");
+            }
+        }
+        Ok(metrics)
+    }
+
     pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
         let all_stats = parse_net_dev(content)?;
         let stats: Vec<&NetStats> = all_stats
@@ -143,6 +221,46 @@ impl NetworkCollector {
                 "Total transmit drops.",
                 Box::new(|s: &NetStats| s.tx_drop as f64),
             ),
+            (
+                "sysmetrics_network_receive_fifo_errors_total",
+                "Total receive FIFO buffer errors.",
+                Box::new(|s: &NetStats| s.rx_fifo as f64),
+            ),
+            (
+                "sysmetrics_network_transmit_fifo_errors_total",
+                "Total transmit FIFO buffer errors.",
+                Box::new(|s: &NetStats| s.tx_fifo as f64),
+            ),
+            (
+                "sysmetrics_network_receive_frame_errors_total",
+                "Total receive frame alignment errors.",
+                Box::new(|s: &NetStats| s.rx_frame as f64),
+            ),
+            (
+                "sysmetrics_network_transmit_carrier_errors_total",
+                "Total transmit carrier losses.",
+                Box::new(|s: &NetStats| s.tx_carrier as f64),
+            ),
+            (
+                "sysmetrics_network_transmit_collisions_total",
+                "Total collisions detected while transmitting.",
+                Box::new(|s: &NetStats| s.tx_colls as f64),
+            ),
+            (
+                "sysmetrics_network_receive_compressed_total",
+                "Total compressed packets received.",
+                Box::new(|s: &NetStats| s.rx_compressed as f64),
+            ),
+            (
+                "sysmetrics_network_transmit_compressed_total",
+                "Total compressed packets transmitted.",
+                Box::new(|s: &NetStats| s.tx_compressed as f64),
+            ),
+            (
+                "sysmetrics_network_receive_multicast_total",
+                "Total multicast packets received.",
+                Box::new(|s: &NetStats| s.rx_multicast as f64),
+            ),
         ];
 
         let mut metrics = Vec::new();
@@ -164,17 +282,120 @@ impl NetworkCollector {
 
         Ok(metrics)
     }
+
+    /// Link properties of every interface under `root` (/sys/class/net)
+    /// that is not excluded.
+    pub fn collect_from_sysfs(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        let devices: Vec<NetDevProperties> = list_sysfs_dir(root, "")?
+            .iter()
+            // Skips plain files such as bonding_masters.
+            .filter(|dir| dir.is_dir())
+            .filter_map(|dir| {
+                let interface = dir.file_name()?.to_str()?;
+                if self.exclude_pattern.is_match(interface) {
+                    return None;
+                }
+                Some(read_net_dev_properties(dir, interface))
+            })
+            .collect();
+
+        let interface_label =
+            |d: &NetDevProperties| vec![("interface".to_string(), d.interface.clone())];
+
+        type MetricDef = (
+            &'static str,
+            &'static str,
+            MetricType,
+            Box<dyn Fn(&NetDevProperties) -> Option<f64>>,
+        );
+        let metric_defs: Vec<MetricDef> = vec![
+            (
+                "sysmetrics_network_speed_bytes",
+                "Negotiated link speed in bytes per second.",
+                MetricType::Gauge,
+                // Drivers report -1 when the speed is unknown.
+                Box::new(|d: &NetDevProperties| {
+                    d.speed
+                        .filter(|s| *s >= 0)
+                        .map(|s| s as f64 * BYTES_PER_MEGABIT)
+                }),
+            ),
+            (
+                "sysmetrics_network_mtu_bytes",
+                "Maximum transmission unit in bytes.",
+                MetricType::Gauge,
+                Box::new(|d: &NetDevProperties| d.mtu.map(|v| v as f64)),
+            ),
+            (
+                "sysmetrics_network_carrier",
+                "Whether the interface has a carrier (1) or not (0).",
+                MetricType::Gauge,
+                Box::new(|d: &NetDevProperties| d.carrier.map(|v| v as f64)),
+            ),
+            (
+                "sysmetrics_network_carrier_changes_total",
+                "Number of times the carrier state changed.",
+                MetricType::Counter,
+                Box::new(|d: &NetDevProperties| d.carrier_changes.map(|v| v as f64)),
+            ),
+        ];
+
+        let mut metrics: Vec<Metric> = metric_defs
+            .iter()
+            .map(|(name, help, metric_type, value_fn)| Metric {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric_type: *metric_type,
+                samples: devices
+                    .iter()
+                    .filter_map(|d| {
+                        value_fn(d).map(|value| MetricSample {
+                            labels: interface_label(d),
+                            value,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        metrics.push(Metric {
+            name: "sysmetrics_network_info".to_string(),
+            help: "Network interface properties.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: devices
+                .iter()
+                .map(|d| {
+                    let mut labels = interface_label(d);
+                    for (key, value) in [
+                        ("address", &d.address),
+                        ("duplex", &d.duplex),
+                        ("operstate", &d.operstate),
+                        ("type", &d.device_type),
+                    ] {
+                        labels.push((key.to_string(), value.clone().unwrap_or_default()));
+                    }
+                    MetricSample { labels, value: 1.0 }
+                })
+                .collect(),
+        });
+
+        Ok(metrics)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const NET_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sys/class/net");
 
     const NET_DEV_FIXTURE: &str = "\
 Inter-|   Receive                                                |  Transmit
  face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
     lo: 1234567  12345    0    0    0     0          0         0  1234567  12345    0    0    0     0       0          0
-  eth0: 9876543  98765    5    2    0     0          0         0  5432198  54321    1    3    0     0       0          0
+  eth0: 9876543  98765    5    2    4     7          1       321  5432198  54321    1    3    6     8       9          2
 ";
 
     #[test]
@@ -194,6 +415,14 @@ Inter-|   Receive                                                |  Transmit
         assert_eq!(stats[1].tx_bytes, 5432198);
         assert_eq!(stats[1].tx_errors, 1);
         assert_eq!(stats[1].tx_drop, 3);
+        assert_eq!(stats[1].rx_fifo, 4);
+        assert_eq!(stats[1].rx_frame, 7);
+        assert_eq!(stats[1].rx_compressed, 1);
+        assert_eq!(stats[1].rx_multicast, 321);
+        assert_eq!(stats[1].tx_fifo, 6);
+        assert_eq!(stats[1].tx_colls, 8);
+        assert_eq!(stats[1].tx_carrier, 9);
+        assert_eq!(stats[1].tx_compressed, 2);
     }
 
     #[test]
@@ -221,7 +450,7 @@ Inter-|   Receive                                                |  Transmit
     fn test_network_collector_metric_values() {
         let collector = NetworkCollector::new("^lo$").unwrap();
         let metrics = collector.collect_from_string(NET_DEV_FIXTURE).unwrap();
-        assert_eq!(metrics.len(), 8);
+        assert_eq!(metrics.len(), 16);
 
         // receive_bytes_total for eth0
         assert_eq!(metrics[0].name, "sysmetrics_network_receive_bytes_total");
@@ -230,6 +459,25 @@ Inter-|   Receive                                                |  Transmit
         // transmit_bytes_total for eth0
         assert_eq!(metrics[1].name, "sysmetrics_network_transmit_bytes_total");
         assert_eq!(metrics[1].samples[0].value, 5432198.0);
+
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_network_receive_frame_errors_total").samples[0].value,
+            7.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_network_transmit_carrier_errors_total").samples[0]
+                .value,
+            9.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_network_transmit_collisions_total").samples[0].value,
+            8.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_network_receive_multicast_total").samples[0].value,
+            321.0
+        );
+        assert!(metrics.iter().all(|m| m.metric_type == MetricType::Counter));
     }
 
     #[test]
@@ -246,4 +494,107 @@ br-abc123: 300 30 0 0 0 0 0 0 400 40 0 0 0 0 0 0
         assert_eq!(metrics[0].samples[0].labels[0].1, "docker0");
         assert_eq!(metrics[0].samples[1].labels[0].1, "br-abc123");
     }
+
+    #[test]
+    fn test_read_net_dev_properties() {
+        let props = read_net_dev_properties(&Path::new(NET_FIXTURE_DIR).join("eth0"), "eth0");
+        assert_eq!(
+            props,
+            NetDevProperties {
+                interface: "eth0".to_string(),
+                speed: Some(1000),
+                mtu: Some(1500),
+                carrier: Some(1),
+                carrier_changes: Some(3),
+                operstate: Some("up".to_string()),
+                duplex: Some("full".to_string()),
+                address: Some("52:54:00:12:34:56".to_string()),
+                device_type: Some("1".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_network_collector_sysfs_link_metrics() {
+        let collector = NetworkCollector::new("^veth").unwrap();
+        let metrics = collector
+            .collect_from_sysfs(Path::new(NET_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics.len(), 5);
+
+        // eth1 reports -1 (link down) and lo has no speed.
+        let speed = &metrics[0];
+        assert_eq!(speed.name, "sysmetrics_network_speed_bytes");
+        assert_eq!(speed.samples.len(), 1);
+        assert_eq!(speed.samples[0].labels[0].1, "eth0");
+        assert_eq!(speed.samples[0].value, 125_000_000.0);
+
+        let mtu = &metrics[1];
+        let interfaces: Vec<&str> = mtu.samples.iter().map(|s| s.labels[0].1.as_str()).collect();
+        assert_eq!(interfaces, vec!["eth0", "eth1", "lo"]);
+        assert_eq!(mtu.samples[1].value, 9000.0);
+
+        // carrier is unreadable while eth1 is down.
+        let carrier = &metrics[2];
+        assert_eq!(carrier.samples.len(), 2);
+
+        let changes = &metrics[3];
+        assert_eq!(changes.metric_type, MetricType::Counter);
+        assert_eq!(changes.samples[0].value, 3.0);
+    }
+
+    #[test]
+    fn test_network_collector_sysfs_info() {
+        let collector = NetworkCollector::new("^(lo|veth)").unwrap();
+        let metrics = collector
+            .collect_from_sysfs(Path::new(NET_FIXTURE_DIR))
+            .unwrap();
+        let info = metrics
+            .iter()
+            .find(|m| m.name == "sysmetrics_network_info")
+            .unwrap();
+        assert_eq!(info.samples.len(), 2);
+        assert_eq!(
+            info.samples[1].labels,
+            vec![
+                ("interface".to_string(), "eth1".to_string()),
+                ("address".to_string(), "52:54:00:ab:cd:ef".to_string()),
+                ("duplex".to_string(), "unknown".to_string()),
+                ("operstate".to_string(), "down".to_string()),
+                ("type".to_string(), "1".to_string()),
+            ]
+        );
+        assert_eq!(info.samples[1].value, 1.0);
+    }
+
+    #[test]
+    fn test_network_collector_sysfs_missing_root() {
+        let collector = NetworkCollector::new("^lo$").unwrap();
+        assert_missing_root_not_supported(|root| collector.collect_from_sysfs(root));
+    }
+
+    #[test]
+    fn test_network_collector_sysfs_error_keeps_proc_metrics() {
+        // A file instead of a directory fails to list like an unreadable
+        // /sys/class/net would.
+        let sysfs_root = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/proc/loadavg"
+        ));
+        let collector = NetworkCollector::new("^lo$").unwrap();
+        assert!(matches!(
+            collector.collect_from_sysfs(sysfs_root),
+            Err(CollectorError::FileRead { .. })
+        ));
+        let metrics = collector
+            .collect_with_sysfs(NET_DEV_FIXTURE, sysfs_root)
+            .unwrap();
+        assert_eq!(
+            metrics.len(),
+            collector
+                .collect_from_string(NET_DEV_FIXTURE)
+                .unwrap()
+                .len()
+        );
+    }
 }
@@ -0,0 +1,244 @@
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::fs;
+use std::io;
+
+const PROC_PRESSURE_CPU_PATH: &str = "/proc/pressure/cpu";
+const PROC_PRESSURE_MEMORY_PATH: &str = "/proc/pressure/memory";
+const PROC_PRESSURE_IO_PATH: &str = "/proc/pressure/io";
+const MICROSECONDS_PER_SECOND: f64 = 1_000_000.0;
+
+/// Cumulative stall times from a single /proc/pressure file, in microseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PressureStats {
+    /// Time in which at least one task was stalled on the resource.
+    pub some_total: u64,
+    /// Time in which all non-idle tasks were stalled simultaneously.
+    /// Absent for CPU on kernels before 5.13.
+    pub full_total: Option<u64>,
+}
+
+/// Parse the content of a /proc/pressure/{cpu,memory,io} file.
+///
+/// Format:
+/// ```text
+/// some avg10=0.00 avg60=0.00 avg300=0.00 total=12345
+/// full avg10=0.00 avg60=0.00 avg300=0.00 total=6789
+/// ```
+pub fn parse_pressure(content: &str, path: &str) -> Result<PressureStats, CollectorError> {
+    let mut some_total = None;
+    let mut full_total = None;
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(kind) = parts.next() else {
+            continue;
+        };
+        let raw = parts
+            .find_map(|p| p.strip_prefix("total="))
+            .ok_or_else(|| CollectorError::Parse {
+                path: path.to_string(),
+                field: format!("{} total", kind),
+                raw: line.to_string(),
+            })?;
+        let total = raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+            path: path.to_string(),
+            field: format!("{} total", kind),
+            raw: raw.to_string(),
+        })?;
+        match kind {
+            "some" => some_total = Some(total),
+            "full" => full_total = Some(total),
+            _ => {}
+        }
+    }
+    Ok(PressureStats {
+        some_total: some_total.ok_or_else(|| CollectorError::Parse {
+            path: path.to_string(),
+            field: "some".to_string(),
+            raw: "some line not found".to_string(),
+        })?,
+        full_total,
+    })
+}
+
+/// Read a pressure file, mapping a missing file or a kernel booted with
+/// `psi=0` (which fails reads with EOPNOTSUPP) to `NotSupported`.
+fn read_pressure_file(path: &str) -> Result<String, CollectorError> {
+    fs::read_to_string(path).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound || e.raw_os_error() == Some(libc::EOPNOTSUPP) {
+            CollectorError::NotSupported {
+                path: path.to_string(),
+            }
+        } else {
+            CollectorError::FileRead {
+                path: path.to_string(),
+                source: e,
+            }
+        }
+    })
+}
+
+pub struct PressureCollector;
+
+impl Collector for PressureCollector {
+    fn name(&self) -> &'static str {
+        "pressure"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        let cpu = read_pressure_file(PROC_PRESSURE_CPU_PATH)?;
+        let memory = read_pressure_file(PROC_PRESSURE_MEMORY_PATH)?;
+        let io = read_pressure_file(PROC_PRESSURE_IO_PATH)?;
+        self.collect_from_string(&cpu, &memory, &io)
+    }
+}
+
+impl PressureCollector {
+    pub fn collect_from_string(
+        &self,
+        cpu: &str,
+        memory: &str,
+        io: &str,
+    ) -> Result<Vec<Metric>, CollectorError> {
+        let cpu = parse_pressure(cpu, PROC_PRESSURE_CPU_PATH)?;
+        let memory = parse_pressure(memory, PROC_PRESSURE_MEMORY_PATH)?;
+        let io = parse_pressure(io, PROC_PRESSURE_IO_PATH)?;
+
+        // The system-wide CPU "full" line is always zero (it only has meaning
+        // per cgroup), so only "some" is exported for CPU.
+        let counters = vec![
+            (
+                "sysmetrics_pressure_cpu_waiting_seconds_total",
+                "Total time in seconds that processes have waited for CPU time.",
+                Some(cpu.some_total),
+            ),
+            (
+                "sysmetrics_pressure_memory_waiting_seconds_total",
+                "Total time in seconds that processes have waited for memory.",
+                Some(memory.some_total),
+            ),
+            (
+                "sysmetrics_pressure_memory_stalled_seconds_total",
+                "Total time in seconds that no process could make progress due to memory congestion.",
+                memory.full_total,
+            ),
+            (
+                "sysmetrics_pressure_io_waiting_seconds_total",
+                "Total time in seconds that processes have waited due to I/O congestion.",
+                Some(io.some_total),
+            ),
+            (
+                "sysmetrics_pressure_io_stalled_seconds_total",
+                "Total time in seconds that no process could make progress due to I/O congestion.",
+                io.full_total,
+            ),
+        ];
+
+        Ok(counters
+            .into_iter()
+            .filter_map(|(name, help, total_us)| {
+                total_us.map(|total_us| Metric {
+                    name: name.to_string(),
+                    help: help.to_string(),
+                    metric_type: MetricType::Counter,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value: total_us as f64 / MICROSECONDS_PER_SECOND,
+                    }],
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CPU_FIXTURE: &str = "\
+some avg10=13.30 avg60=7.68 avg300=5.28 total=34188572
+full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+";
+
+    const CPU_FIXTURE_OLD_KERNEL: &str = "\
+some avg10=1.00 avg60=2.00 avg300=3.00 total=1000000
+";
+
+    const MEMORY_FIXTURE: &str = "\
+some avg10=0.00 avg60=0.10 avg300=0.05 total=2500000
+full avg10=0.00 avg60=0.02 avg300=0.01 total=1500000
+";
+
+    const IO_FIXTURE: &str = "\
+some avg10=0.50 avg60=0.30 avg300=0.20 total=7000000
+full avg10=0.40 avg60=0.20 avg300=0.10 total=5000000
+";
+
+    #[test]
+    fn test_parse_pressure() {
+        let stats = parse_pressure(MEMORY_FIXTURE, PROC_PRESSURE_MEMORY_PATH).unwrap();
+        assert_eq!(
+            stats,
+            PressureStats {
+                some_total: 2500000,
+                full_total: Some(1500000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_pressure_without_full() {
+        let stats = parse_pressure(CPU_FIXTURE_OLD_KERNEL, PROC_PRESSURE_CPU_PATH).unwrap();
+        assert_eq!(stats.some_total, 1000000);
+        assert_eq!(stats.full_total, None);
+    }
+
+    #[test]
+    fn test_parse_pressure_malformed() {
+        assert!(parse_pressure("", PROC_PRESSURE_IO_PATH).is_err());
+        assert!(parse_pressure("some avg10=0.00\n", PROC_PRESSURE_IO_PATH).is_err());
+        assert!(parse_pressure("some total=abc\n", PROC_PRESSURE_IO_PATH).is_err());
+    }
+
+    #[test]
+    fn test_pressure_collector_metrics() {
+        let collector = PressureCollector;
+        let metrics = collector
+            .collect_from_string(CPU_FIXTURE, MEMORY_FIXTURE, IO_FIXTURE)
+            .unwrap();
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "sysmetrics_pressure_cpu_waiting_seconds_total",
+                "sysmetrics_pressure_memory_waiting_seconds_total",
+                "sysmetrics_pressure_memory_stalled_seconds_total",
+                "sysmetrics_pressure_io_waiting_seconds_total",
+                "sysmetrics_pressure_io_stalled_seconds_total",
+            ]
+        );
+        assert!(metrics.iter().all(|m| m.metric_type == MetricType::Counter));
+        assert!((metrics[0].samples[0].value - 34.188572).abs() < 1e-9);
+        assert_eq!(metrics[2].samples[0].value, 1.5);
+        assert_eq!(metrics[4].samples[0].value, 5.0);
+    }
+
+    #[test]
+    fn test_pressure_collector_missing_full_line() {
+        let io_without_full = "some avg10=0.00 avg60=0.00 avg300=0.00 total=42\n";
+        let collector = PressureCollector;
+        let metrics = collector
+            .collect_from_string(CPU_FIXTURE_OLD_KERNEL, MEMORY_FIXTURE, io_without_full)
+            .unwrap();
+        assert_eq!(metrics.len(), 4);
+        assert!(!metrics
+            .iter()
+            .any(|m| m.name == "sysmetrics_pressure_io_stalled_seconds_total"));
+    }
+
+    #[test]
+    fn test_read_pressure_file_missing_is_not_supported() {
+        let result = read_pressure_file("/nonexistent/pressure/cpu");
+        assert!(matches!(result, Err(CollectorError::NotSupported { .. })));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::collector::processes::{is_vanished, list_pids, parse_pid_stat, PidStat};
+use crate::collector::{read_sysfs_value, Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const PROC_PATH: &str = "/proc";
+
+/// States that are always reported, even when no process is in them, so
+/// alerts on e.g. `state="Z"` do not see an absent series.
+const PROCESS_STATES: &[char] = &['R', 'S', 'D', 'Z', 'T', 'I'];
+
+/// Process state counts and thread total from /proc/[pid]/stat, and
+/// optionally thread state counts from /proc/[pid]/task/[tid]/stat.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessStateCounts {
+    /// States of processes, i.e. of their main threads.
+    pub states: BTreeMap<char, u64>,
+    /// States of all threads; empty unless thread states were scanned.
+    pub thread_states: BTreeMap<char, u64>,
+    pub threads: u64,
+}
+
+/// Read and parse a stat file. Returns `Ok(None)` if the process or thread
+/// exited, which can also leave an empty or truncated read.
+fn read_stat(stat_path: &Path) -> Result<Option<PidStat>, CollectorError> {
+    let content = match fs::read_to_string(stat_path) {
+        Ok(content) => content,
+        Err(e) if is_vanished(&e) => return Ok(None),
+        Err(e) => {
+            return Err(CollectorError::FileRead {
+                path: stat_path.display().to_string(),
+                source: e,
+            })
+        }
+    };
+    match parse_pid_stat(&content, &stat_path.display().to_string()) {
+        Ok(stat) => Ok(Some(stat)),
+        Err(e) => {
+            tracing::debug!(error = %e, "skipping unparsable stat");
+            Ok(None)
+        }
+    }
+}
+
+/// Scan all PIDs under `proc_root`, and with `thread_states` also their
+/// threads. Processes and threads that exit during the scan are skipped.
+pub fn scan_process_states(
+    proc_root: &Path,
+    thread_states: bool,
+) -> Result<ProcessStateCounts, CollectorError> {
+    let all_states = || PROCESS_STATES.iter().map(|s| (*s, 0)).collect();
+    let mut counts = ProcessStateCounts {
+        states: all_states(),
+        thread_states: if thread_states {
+            all_states()
+        } else {
+            BTreeMap::new()
+        },
+        threads: 0,
+    };
+    for (_, dir) in list_pids(proc_root)? {
+        let Some(stat) = read_stat(&dir.join("stat"))? else {
+            continue;
+        };
+        *counts.states.entry(stat.state).or_insert(0) += 1;
+        counts.threads += stat.num_threads;
+        if !thread_states {
+            continue;
+        }
+
+        // task/ disappears with the process.
+        let Ok(tasks) = list_pids(&dir.join("task")) else {
+            continue;
+        };
+        for (_, task_dir) in tasks {
+            if let Some(task) = read_stat(&task_dir.join("stat"))? {
+                *counts.thread_states.entry(task.state).or_insert(0) += 1;
+            }
+        }
+    }
+    Ok(counts)
+}
+
+pub struct ProcessStatesCollector {
+    thread_states: bool,
+}
+
+impl ProcessStatesCollector {
+    /// With `thread_states`, the state of every thread is also exported,
+    /// at the cost of one more file read per thread.
+    pub fn new(thread_states: bool) -> Self {
+        Self { thread_states }
+    }
+}
+
+impl Collector for ProcessStatesCollector {
+    fn name(&self) -> &'static str {
+        "process_states"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(PROC_PATH))
+    }
+}
+
+impl ProcessStatesCollector {
+    pub fn collect_from_dir(&self, proc_root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        let counts = scan_process_states(proc_root, self.thread_states)?;
+
+        let state_samples = |states: &BTreeMap<char, u64>| -> Vec<MetricSample> {
+            states
+                .iter()
+                .map(|(state, count)| MetricSample {
+                    labels: vec![("state".to_string(), state.to_string())],
+                    value: *count as f64,
+                })
+                .collect()
+        };
+        let limit_sample = |file: &str| -> Vec<MetricSample> {
+            read_sysfs_value::<u64>(&proc_root.join("sys/kernel").join(file))
+                .map(|value| MetricSample {
+                    labels: vec![],
+                    value: value as f64,
+                })
+                .into_iter()
+                .collect()
+        };
+
+        let mut metrics = vec![
+            Metric {
+                name: "sysmetrics_processes_state".to_string(),
+                help: "Number of processes in each state, by the state of their main thread."
+                    .to_string(),
+                metric_type: MetricType::Gauge,
+                samples: state_samples(&counts.states),
+            },
+            Metric {
+                name: "sysmetrics_processes_threads".to_string(),
+                help: "Total number of threads.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: vec![MetricSample {
+                    labels: vec![],
+                    value: counts.threads as f64,
+                }],
+            },
+            Metric {
+                name: "sysmetrics_processes_pid_max".to_string(),
+                help: "Maximum PID value from /proc/sys/kernel/pid_max.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: limit_sample("pid_max"),
+            },
+            Metric {
+                name: "sysmetrics_processes_threads_max".to_string(),
+                help: "System-wide thread limit from /proc/sys/kernel/threads-max.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: limit_sample("threads-max"),
+            },
+        ];
+        if self.thread_states {
+            metrics.push(Metric {
+                name: "sysmetrics_processes_thread_state".to_string(),
+                help: "Number of threads in each state.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: state_samples(&counts.thread_states),
+            });
+        }
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc");
+    const PROC_LATER_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc_later");
+
+    #[test]
+    fn test_scan_process_states() {
+        let counts = scan_process_states(Path::new(PROC_FIXTURE_DIR), false).unwrap();
+        assert_eq!(counts.states[&'R'], 1);
+        assert_eq!(counts.states[&'S'], 3);
+        assert_eq!(counts.states[&'D'], 1);
+        assert_eq!(counts.states[&'Z'], 1);
+        assert_eq!(counts.states[&'I'], 1);
+        // Unseen states are still present.
+        assert_eq!(counts.states[&'T'], 0);
+        assert_eq!(counts.threads, 10);
+        // Threads are only walked on request.
+        assert!(counts.thread_states.is_empty());
+    }
+
+    #[test]
+    fn test_scan_process_states_threads() {
+        let counts = scan_process_states(Path::new(PROC_FIXTURE_DIR), true).unwrap();
+        // postgres 100 and 101 have running threads besides their
+        // sleeping and blocked main threads.
+        assert_eq!(counts.thread_states[&'R'], 3);
+        assert_eq!(counts.thread_states[&'S'], 4);
+        assert_eq!(counts.thread_states[&'D'], 1);
+        assert_eq!(counts.thread_states[&'T'], 0);
+        assert_eq!(counts.thread_states.values().sum::<u64>(), counts.threads);
+    }
+
+    #[test]
+    fn test_process_states_collector_metrics() {
+        let metrics = ProcessStatesCollector::new(false)
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics.len(), 4);
+
+        let states = &metrics[0];
+        assert_eq!(states.name, "sysmetrics_processes_state");
+        assert_eq!(states.samples.len(), 6);
+        let zombies = states
+            .samples
+            .iter()
+            .find(|s| s.labels[0].1 == "Z")
+            .unwrap();
+        assert_eq!(zombies.value, 1.0);
+
+        assert_eq!(metrics[1].samples[0].value, 10.0);
+        assert_eq!(metrics[2].samples[0].value, 4194304.0);
+        assert_eq!(metrics[3].samples[0].value, 127319.0);
+    }
+
+    #[test]
+    fn test_process_states_collector_thread_states() {
+        let metrics = ProcessStatesCollector::new(true)
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics.len(), 5);
+        let threads = &metrics[4];
+        assert_eq!(threads.name, "sysmetrics_processes_thread_state");
+        assert_eq!(threads.samples.len(), 6);
+        assert_eq!(threads.samples.iter().map(|s| s.value).sum::<f64>(), 10.0);
+    }
+
+    #[test]
+    fn test_process_states_collector_missing_limits() {
+        // Limits are optional; a root without sys/kernel still reports
+        // states. PID 400 has a truncated stat file and is skipped.
+        let metrics = ProcessStatesCollector::new(false)
+            .collect_from_dir(Path::new(PROC_LATER_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics[0].samples.iter().map(|s| s.value).sum::<f64>(), 1.0);
+        assert!(metrics[2].samples.is_empty());
+        assert!(metrics[3].samples.is_empty());
+    }
+}
@@ -0,0 +1,680 @@
+use crate::collector::cpu::USER_HZ;
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+const PROC_PATH: &str = "/proc";
+
+/// Fields of /proc/[pid]/stat used by the process collectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PidStat {
+    pub pid: u64,
+    pub comm: String,
+    pub state: char,
+    /// User and system CPU time in clock ticks.
+    pub utime: u64,
+    pub stime: u64,
+    pub num_threads: u64,
+    /// Start time after boot in clock ticks.
+    pub starttime: u64,
+    /// Virtual memory size in bytes.
+    pub vsize: u64,
+}
+
+/// Parse the content of /proc/[pid]/stat.
+///
+/// `comm` is enclosed in parentheses and may itself contain spaces and
+/// parentheses, so the fields are located relative to the last `)`.
+pub fn parse_pid_stat(content: &str, path: &str) -> Result<PidStat, CollectorError> {
+    let parse_err = |field: &str, raw: &str| CollectorError::Parse {
+        path: path.to_string(),
+        field: field.to_string(),
+        raw: raw.to_string(),
+    };
+
+    let content = content.trim();
+    let (open, close) = match (content.find('('), content.rfind(')')) {
+        (Some(open), Some(close)) if open < close => (open, close),
+        _ => return Err(parse_err("comm", content)),
+    };
+    let pid_raw = content[..open].trim();
+    let pid = pid_raw
+        .parse::<u64>()
+        .map_err(|_| parse_err("pid", pid_raw))?;
+    let comm = content[open + 1..close].to_string();
+
+    // Fields after comm, starting with field 3 (state) of proc(5).
+    let fields: Vec<&str> = content[close + 1..].split_whitespace().collect();
+    if fields.len() < 21 {
+        return Err(parse_err("stat fields", content));
+    }
+    let parse_field = |idx: usize, field: &str| -> Result<u64, CollectorError> {
+        fields[idx]
+            .parse::<u64>()
+            .map_err(|_| parse_err(field, fields[idx]))
+    };
+
+    Ok(PidStat {
+        pid,
+        comm,
+        state: fields[0]
+            .chars()
+            .next()
+            .ok_or_else(|| parse_err("state", fields[0]))?,
+        utime: parse_field(11, "utime")?,
+        stime: parse_field(12, "stime")?,
+        num_threads: parse_field(17, "num_threads")?,
+        starttime: parse_field(19, "starttime")?,
+        vsize: parse_field(20, "vsize")?,
+    })
+}
+
+/// Extract a `Key:   value kB` field from /proc/[pid]/status, in bytes.
+/// Kernel threads have no `Vm*` fields.
+pub fn parse_status_kb(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (k, rest) = line.split_once(':')?;
+        if k != key {
+            return None;
+        }
+        let kb = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+        Some(kb * 1024)
+    })
+}
+
+/// Storage I/O from /proc/[pid]/io, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PidIo {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+/// Parse the content of /proc/[pid]/io.
+pub fn parse_pid_io(content: &str, path: &str) -> Result<PidIo, CollectorError> {
+    let mut io = PidIo::default();
+    for line in content.lines() {
+        let Some((key, raw)) = line.split_once(':') else {
+            continue;
+        };
+        let target = match key {
+            "read_bytes" => &mut io.read_bytes,
+            "write_bytes" => &mut io.write_bytes,
+            _ => continue,
+        };
+        let raw = raw.trim();
+        *target = raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+            path: path.to_string(),
+            field: key.to_string(),
+            raw: raw.to_string(),
+        })?;
+    }
+    Ok(io)
+}
+
+/// List the numeric (PID) entries of a procfs root.
+pub fn list_pids(proc_root: &Path) -> Result<Vec<(u64, PathBuf)>, CollectorError> {
+    let entries = fs::read_dir(proc_root).map_err(|e| CollectorError::FileRead {
+        path: proc_root.display().to_string(),
+        source: e,
+    })?;
+    let mut pids: Vec<(u64, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|e| {
+            let pid = e.file_name().to_str()?.parse::<u64>().ok()?;
+            Some((pid, e.path()))
+        })
+        .collect();
+    pids.sort();
+    Ok(pids)
+}
+
+/// Whether a read error means the process went away during the scan.
+/// Reads of an exited process fail with ENOENT or ESRCH.
+pub fn is_vanished(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::NotFound || e.raw_os_error() == Some(libc::ESRCH)
+}
+
+/// A rule assigning processes to a named group. A process matches if its
+/// name (`comm`) and its command line match the respective patterns; an
+/// unset pattern matches everything.
+pub struct ProcessGroupRule {
+    name: String,
+    comm_pattern: Option<Regex>,
+    cmdline_pattern: Option<Regex>,
+}
+
+impl ProcessGroupRule {
+    pub fn new(
+        name: &str,
+        comm_pattern: Option<&str>,
+        cmdline_pattern: Option<&str>,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.to_string(),
+            comm_pattern: comm_pattern.map(Regex::new).transpose()?,
+            cmdline_pattern: cmdline_pattern.map(Regex::new).transpose()?,
+        })
+    }
+
+    fn matches(&self, comm: &str, cmdline: &str) -> bool {
+        self.comm_pattern.as_ref().is_none_or(|r| r.is_match(comm))
+            && self
+                .cmdline_pattern
+                .as_ref()
+                .is_none_or(|r| r.is_match(cmdline))
+    }
+}
+
+/// Resource usage summed over all processes of a group. The CPU and I/O
+/// counters also include processes of the group that have exited.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GroupTotals {
+    pub processes: u64,
+    pub threads: u64,
+    pub user_seconds: f64,
+    pub system_seconds: f64,
+    pub resident_bytes: u64,
+    pub open_fds: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+impl GroupTotals {
+    fn add_counters(&mut self, counters: &ProcessCounters) {
+        self.user_seconds += counters.user_seconds;
+        self.system_seconds += counters.system_seconds;
+        self.read_bytes += counters.read_bytes;
+        self.write_bytes += counters.write_bytes;
+    }
+}
+
+/// CPU and I/O counters of a single process.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ProcessCounters {
+    user_seconds: f64,
+    system_seconds: f64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+impl ProcessCounters {
+    /// The larger of each counter, so a process whose io file became
+    /// unreadable does not move its group backwards.
+    fn max(self, other: Self) -> Self {
+        Self {
+            user_seconds: self.user_seconds.max(other.user_seconds),
+            system_seconds: self.system_seconds.max(other.system_seconds),
+            read_bytes: self.read_bytes.max(other.read_bytes),
+            write_bytes: self.write_bytes.max(other.write_bytes),
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.user_seconds += other.user_seconds;
+        self.system_seconds += other.system_seconds;
+        self.read_bytes += other.read_bytes;
+        self.write_bytes += other.write_bytes;
+    }
+}
+
+/// One process that matched a rule during a scan.
+struct ProcessReading {
+    /// PID and start time, so a reused PID is a different process.
+    key: (u64, u64),
+    group: String,
+    threads: u64,
+    resident_bytes: u64,
+    open_fds: u64,
+    counters: ProcessCounters,
+}
+
+/// Processes seen by the previous scrape and the counters of the ones that
+/// have exited since the collector started, by group.
+#[derive(Default)]
+struct GroupHistory {
+    seen: HashMap<(u64, u64), (String, ProcessCounters)>,
+    exited: HashMap<String, ProcessCounters>,
+}
+
+pub struct ProcessesCollector {
+    rules: Vec<ProcessGroupRule>,
+    history: Mutex<GroupHistory>,
+}
+
+impl ProcessesCollector {
+    /// Rules are evaluated in order; a process belongs to the first rule it
+    /// matches and is ignored if it matches none.
+    pub fn new(rules: Vec<ProcessGroupRule>) -> Self {
+        Self {
+            rules,
+            history: Mutex::new(GroupHistory::default()),
+        }
+    }
+
+    /// Read one process. Returns `Ok(None)` if the process exited during
+    /// the scan, its stat file cannot be parsed, or it matches no rule.
+    fn read_process(&self, dir: &Path) -> Result<Option<ProcessReading>, CollectorError> {
+        let stat_path = dir.join("stat");
+        let stat_content = match fs::read_to_string(&stat_path) {
+            Ok(content) => content,
+            Err(e) if is_vanished(&e) => return Ok(None),
+            Err(e) => {
+                return Err(CollectorError::FileRead {
+                    path: stat_path.display().to_string(),
+                    source: e,
+                })
+            }
+        };
+        // A process that exits while its stat file is read can leave an
+        // empty or truncated read.
+        let stat = match parse_pid_stat(&stat_content, &stat_path.display().to_string()) {
+            Ok(stat) => stat,
+            Err(e) => {
+                tracing::debug!(error = %e, "skipping process with unparsable stat");
+                return Ok(None);
+            }
+        };
+
+        let cmdline = fs::read(dir.join("cmdline"))
+            .map(|raw| {
+                String::from_utf8_lossy(&raw)
+                    .trim_end_matches('\0')
+                    .replace('\0', " ")
+            })
+            .unwrap_or_default();
+
+        let Some(rule) = self.rules.iter().find(|r| r.matches(&stat.comm, &cmdline)) else {
+            return Ok(None);
+        };
+
+        // The remaining files are best effort: the process may exit at any
+        // point, and io and fd/ are unreadable for other users' processes
+        // without CAP_SYS_PTRACE.
+        let resident_bytes = fs::read_to_string(dir.join("status"))
+            .ok()
+            .and_then(|s| parse_status_kb(&s, "VmRSS"))
+            .unwrap_or(0);
+        let io_path = dir.join("io");
+        let io = fs::read_to_string(&io_path)
+            .ok()
+            .and_then(|s| parse_pid_io(&s, &io_path.display().to_string()).ok())
+            .unwrap_or_default();
+        let open_fds = fs::read_dir(dir.join("fd"))
+            .map(|entries| entries.count() as u64)
+            .unwrap_or(0);
+
+        Ok(Some(ProcessReading {
+            key: (stat.pid, stat.starttime),
+            group: rule.name.clone(),
+            threads: stat.num_threads,
+            resident_bytes,
+            open_fds,
+            counters: ProcessCounters {
+                user_seconds: stat.utime as f64 / USER_HZ,
+                system_seconds: stat.stime as f64 / USER_HZ,
+                read_bytes: io.read_bytes,
+                write_bytes: io.write_bytes,
+            },
+        }))
+    }
+}
+
+impl Collector for ProcessesCollector {
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(PROC_PATH))
+    }
+}
+
+impl ProcessesCollector {
+    pub fn collect_from_dir(&self, proc_root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        // Every configured group is reported, even with no processes, so its
+        // series do not disappear while the daemon is down.
+        let mut groups: BTreeMap<String, GroupTotals> = self
+            .rules
+            .iter()
+            .map(|r| (r.name.clone(), GroupTotals::default()))
+            .collect();
+
+        let mut readings = Vec::new();
+        for (_, dir) in list_pids(proc_root)? {
+            readings.extend(self.read_process(&dir)?);
+        }
+
+        // The CPU and I/O counters of a group are kept monotonic by adding
+        // the last seen counters of its exited processes, as
+        // process-exporter does.
+        let mut history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut seen = HashMap::new();
+        for reading in readings {
+            // A process stays in the group it was first seen in, even if
+            // it changes its command line.
+            let (group, counters) = match history.seen.remove(&reading.key) {
+                Some((group, previous)) => (group, previous.max(reading.counters)),
+                None => (reading.group, reading.counters),
+            };
+            let totals = groups.entry(group.clone()).or_default();
+            totals.processes += 1;
+            totals.threads += reading.threads;
+            totals.resident_bytes += reading.resident_bytes;
+            totals.open_fds += reading.open_fds;
+            totals.add_counters(&counters);
+            seen.insert(reading.key, (group, counters));
+        }
+        // Whatever was not seen again has exited since the last scrape.
+        let exited: Vec<_> = history.seen.drain().map(|(_, p)| p).collect();
+        for (group, counters) in exited {
+            history.exited.entry(group).or_default().add(&counters);
+        }
+        history.seen = seen;
+        for (group, counters) in &history.exited {
+            groups
+                .entry(group.clone())
+                .or_default()
+                .add_counters(counters);
+        }
+
+        Ok(group_metrics(&groups))
+    }
+}
+
+/// Build metric families from per-group totals.
+pub fn group_metrics(groups: &BTreeMap<String, GroupTotals>) -> Vec<Metric> {
+    let group_label = |name: &str| vec![("groupname".to_string(), name.to_string())];
+
+    type MetricDef = (
+        &'static str,
+        &'static str,
+        MetricType,
+        Box<dyn Fn(&GroupTotals) -> f64>,
+    );
+    let metric_defs: Vec<MetricDef> = vec![
+        (
+            "sysmetrics_process_group_processes",
+            "Number of processes in the group.",
+            MetricType::Gauge,
+            Box::new(|t: &GroupTotals| t.processes as f64),
+        ),
+        (
+            "sysmetrics_process_group_threads",
+            "Number of threads in the group.",
+            MetricType::Gauge,
+            Box::new(|t: &GroupTotals| t.threads as f64),
+        ),
+        (
+            "sysmetrics_process_group_resident_memory_bytes",
+            "Resident memory of the group in bytes.",
+            MetricType::Gauge,
+            Box::new(|t: &GroupTotals| t.resident_bytes as f64),
+        ),
+        (
+            "sysmetrics_process_group_open_fds",
+            "Number of open file descriptors in the group.",
+            MetricType::Gauge,
+            Box::new(|t: &GroupTotals| t.open_fds as f64),
+        ),
+        (
+            "sysmetrics_process_group_read_bytes_total",
+            "Bytes read from storage by the group.",
+            MetricType::Counter,
+            Box::new(|t: &GroupTotals| t.read_bytes as f64),
+        ),
+        (
+            "sysmetrics_process_group_written_bytes_total",
+            "Bytes written to storage by the group.",
+            MetricType::Counter,
+            Box::new(|t: &GroupTotals| t.write_bytes as f64),
+        ),
+    ];
+
+    let mut metrics = vec![Metric {
+        name: "sysmetrics_process_group_cpu_seconds_total".to_string(),
+        help: "CPU time consumed by the group in seconds.".to_string(),
+        metric_type: MetricType::Counter,
+        samples: groups
+            .iter()
+            .flat_map(|(name, t)| {
+                [("user", t.user_seconds), ("system", t.system_seconds)]
+                    .into_iter()
+                    .map(move |(mode, value)| {
+                        let mut labels = group_label(name);
+                        labels.push(("mode".to_string(), mode.to_string()));
+                        MetricSample { labels, value }
+                    })
+            })
+            .collect(),
+    }];
+
+    for (name, help, metric_type, value_fn) in &metric_defs {
+        metrics.push(Metric {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: *metric_type,
+            samples: groups
+                .iter()
+                .map(|(group, t)| MetricSample {
+                    labels: group_label(group),
+                    value: value_fn(t),
+                })
+                .collect(),
+        });
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::find_metric;
+
+    const PROC_FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc");
+    /// The same host at a later scrape: PID 101 has exited, PID 100 has
+    /// used more CPU, and PID 400 has a truncated stat file.
+    const PROC_LATER_FIXTURE_DIR: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc_later");
+
+    const STAT_FIXTURE: &str = "16674 (cat) R 16670 16674 16670 0 -1 4194304 80 0 0 0 12 34 0 0 20 0 3 0 151972 2703360 285 18446744073709551615 0 0 0 0";
+
+    fn default_rules() -> Vec<ProcessGroupRule> {
+        vec![
+            ProcessGroupRule::new("postgres", Some("^postgres$"), None).unwrap(),
+            ProcessGroupRule::new("nginx", None, Some("^nginx: ")).unwrap(),
+            ProcessGroupRule::new("redis", Some("^redis-server$"), None).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_parse_pid_stat() {
+        let stat = parse_pid_stat(STAT_FIXTURE, "/proc/16674/stat").unwrap();
+        assert_eq!(
+            stat,
+            PidStat {
+                pid: 16674,
+                comm: "cat".to_string(),
+                state: 'R',
+                utime: 12,
+                stime: 34,
+                num_threads: 3,
+                starttime: 151972,
+                vsize: 2703360,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_pid_stat_comm_with_spaces_and_parens() {
+        let input = "42 (my (weird) proc) S 1 42 42 0 -1 0 0 0 0 0 5 6 0 0 20 0 1 0 100 0 0";
+        let stat = parse_pid_stat(input, "/proc/42/stat").unwrap();
+        assert_eq!(stat.comm, "my (weird) proc");
+        assert_eq!(stat.state, 'S');
+        assert_eq!(stat.utime, 5);
+    }
+
+    #[test]
+    fn test_parse_pid_stat_malformed() {
+        assert!(parse_pid_stat("", "/proc/1/stat").is_err());
+        assert!(parse_pid_stat("1 (init) S 0 1", "/proc/1/stat").is_err());
+        assert!(parse_pid_stat("abc (init) S", "/proc/1/stat").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_kb() {
+        let status = "Name:\tcat\nVmSize:\t    3340 kB\nVmRSS:\t    1804 kB\n";
+        assert_eq!(parse_status_kb(status, "VmRSS"), Some(1804 * 1024));
+        assert_eq!(parse_status_kb(status, "VmSwap"), None);
+    }
+
+    #[test]
+    fn test_parse_pid_io() {
+        let content = "rchar: 3980\nwchar: 10\nread_bytes: 4096\nwrite_bytes: 8192\n";
+        let io = parse_pid_io(content, "/proc/1/io").unwrap();
+        assert_eq!(
+            io,
+            PidIo {
+                read_bytes: 4096,
+                write_bytes: 8192,
+            }
+        );
+        assert!(parse_pid_io("read_bytes: x\n", "/proc/1/io").is_err());
+    }
+
+    #[test]
+    fn test_process_group_rule_matching() {
+        let rule = ProcessGroupRule::new("pg", Some("^postgres$"), Some("checkpointer")).unwrap();
+        assert!(rule.matches("postgres", "postgres: checkpointer"));
+        assert!(!rule.matches("postgres", "postgres: walwriter"));
+        assert!(!rule.matches("nginx", "postgres: checkpointer"));
+
+        let catch_all = ProcessGroupRule::new("other", None, None).unwrap();
+        assert!(catch_all.matches("anything", ""));
+    }
+
+    #[test]
+    fn test_processes_collector_groups() {
+        let collector = ProcessesCollector::new(default_rules());
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+
+        let count = find_metric(&metrics, "sysmetrics_process_group_processes");
+        // groups are sorted by name
+        let names: Vec<&str> = count
+            .samples
+            .iter()
+            .map(|s| s.labels[0].1.as_str())
+            .collect();
+        assert_eq!(names, vec!["nginx", "postgres", "redis"]);
+        assert_eq!(count.samples[0].value, 2.0);
+        assert_eq!(count.samples[1].value, 2.0);
+        // configured group without processes is still reported
+        assert_eq!(count.samples[2].value, 0.0);
+
+        let threads = find_metric(&metrics, "sysmetrics_process_group_threads");
+        assert_eq!(threads.samples[1].value, 5.0);
+
+        let rss = find_metric(&metrics, "sysmetrics_process_group_resident_memory_bytes");
+        assert_eq!(rss.samples[1].value, (20000.0 + 10000.0) * 1024.0);
+
+        let fds = find_metric(&metrics, "sysmetrics_process_group_open_fds");
+        assert_eq!(fds.samples[1].value, 5.0);
+
+        let read = find_metric(&metrics, "sysmetrics_process_group_read_bytes_total");
+        assert_eq!(read.metric_type, MetricType::Counter);
+        assert_eq!(read.samples[1].value, 3000.0);
+    }
+
+    #[test]
+    fn test_processes_collector_cpu_seconds() {
+        let collector = ProcessesCollector::new(default_rules());
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        let cpu = find_metric(&metrics, "sysmetrics_process_group_cpu_seconds_total");
+        assert_eq!(cpu.samples.len(), 6);
+        assert_eq!(
+            cpu.samples[2].labels,
+            vec![
+                ("groupname".to_string(), "postgres".to_string()),
+                ("mode".to_string(), "user".to_string()),
+            ]
+        );
+        // (1000 + 500) ticks / 100
+        assert!((cpu.samples[2].value - 15.0).abs() < 0.001);
+        assert!((cpu.samples[3].value - 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_processes_collector_first_rule_wins() {
+        let rules = vec![
+            ProcessGroupRule::new("master", None, Some("master process")).unwrap(),
+            ProcessGroupRule::new("nginx", Some("^nginx$"), None).unwrap(),
+        ];
+        let metrics = ProcessesCollector::new(rules)
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        let count = find_metric(&metrics, "sysmetrics_process_group_processes");
+        assert_eq!(count.samples[0].labels[0].1, "master");
+        assert_eq!(count.samples[0].value, 1.0);
+        assert_eq!(count.samples[1].value, 1.0);
+    }
+
+    #[test]
+    fn test_processes_collector_vanished_process() {
+        // PID 999 has a directory but no stat file, as if it exited mid-scan.
+        let collector =
+            ProcessesCollector::new(vec![ProcessGroupRule::new("all", None, None).unwrap()]);
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        let count = find_metric(&metrics, "sysmetrics_process_group_processes");
+        assert_eq!(count.samples[0].value, 7.0);
+    }
+
+    #[test]
+    fn test_processes_collector_exited_process_keeps_counters() {
+        let collector = ProcessesCollector::new(default_rules());
+        collector
+            .collect_from_dir(Path::new(PROC_FIXTURE_DIR))
+            .unwrap();
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_LATER_FIXTURE_DIR))
+            .unwrap();
+
+        let count = find_metric(&metrics, "sysmetrics_process_group_processes");
+        assert_eq!(count.samples[1].value, 1.0);
+        // PID 100 now has 1100 user ticks; PID 101 exited with 500.
+        let cpu = find_metric(&metrics, "sysmetrics_process_group_cpu_seconds_total");
+        assert!((cpu.samples[2].value - 16.0).abs() < 0.001);
+        assert!((cpu.samples[3].value - 3.2).abs() < 0.001);
+        let read = find_metric(&metrics, "sysmetrics_process_group_read_bytes_total");
+        assert_eq!(read.samples[1].value, 1200.0 + 2000.0);
+        let written = find_metric(&metrics, "sysmetrics_process_group_written_bytes_total");
+        assert_eq!(written.samples[1].value, 500.0);
+
+        // The exited process is only added once.
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_LATER_FIXTURE_DIR))
+            .unwrap();
+        let cpu = find_metric(&metrics, "sysmetrics_process_group_cpu_seconds_total");
+        assert!((cpu.samples[2].value - 16.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_processes_collector_skips_malformed_stat() {
+        // PID 400 matches the postgres rule but its stat is truncated.
+        let collector = ProcessesCollector::new(default_rules());
+        let metrics = collector
+            .collect_from_dir(Path::new(PROC_LATER_FIXTURE_DIR))
+            .unwrap();
+        let count = find_metric(&metrics, "sysmetrics_process_group_processes");
+        assert_eq!(count.samples[1].value, 1.0);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::collector::cpu::USER_HZ;
+use crate::collector::loadavg::parse_boot_time;
+use crate::collector::processes::{parse_pid_stat, parse_status_kb};
+use crate::collector::{Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::fs;
+use std::path::Path;
+
+/// Soft limit of a resource from /proc/[pid]/limits, e.g. `Max open files`.
+/// Returns `None` if the limit is missing or `unlimited`.
+pub fn parse_limit(content: &str, name: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let rest = line.strip_prefix(name)?;
+        rest.split_whitespace().next()?.parse::<u64>().ok()
+    })
+}
+
+/// Resource usage of the exporter process itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfProcessStats {
+    pub cpu_seconds: f64,
+    pub resident_bytes: Option<u64>,
+    pub virtual_bytes: u64,
+    pub open_fds: Option<u64>,
+    pub max_fds: Option<u64>,
+    pub start_time_seconds: f64,
+}
+
+/// Read the exporter's own statistics from `<proc_root>/self`.
+pub fn read_self_process(proc_root: &Path) -> Result<SelfProcessStats, CollectorError> {
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| CollectorError::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })
+    };
+    let self_dir = proc_root.join("self");
+    let stat_path = self_dir.join("stat");
+    let stat = parse_pid_stat(&read(&stat_path)?, &stat_path.display().to_string())?;
+    let boot_time = parse_boot_time(&read(&proc_root.join("stat"))?)?;
+
+    Ok(SelfProcessStats {
+        cpu_seconds: (stat.utime + stat.stime) as f64 / USER_HZ,
+        resident_bytes: fs::read_to_string(self_dir.join("status"))
+            .ok()
+            .and_then(|s| parse_status_kb(&s, "VmRSS")),
+        virtual_bytes: stat.vsize,
+        open_fds: fs::read_dir(self_dir.join("fd"))
+            .ok()
+            .map(|entries| entries.count() as u64),
+        max_fds: fs::read_to_string(self_dir.join("limits"))
+            .ok()
+            .and_then(|s| parse_limit(&s, "Max open files")),
+        start_time_seconds: boot_time as f64 + stat.starttime as f64 / USER_HZ,
+    })
+}
+
+/// Build the standard `process_*` metric families for the exporter process.
+pub fn self_process_metrics(stats: &SelfProcessStats) -> Vec<Metric> {
+    let metric = |name: &str, help: &str, metric_type: MetricType, value: Option<f64>| Metric {
+        name: name.to_string(),
+        help: help.to_string(),
+        metric_type,
+        samples: value
+            .map(|value| MetricSample {
+                labels: vec![],
+                value,
+            })
+            .into_iter()
+            .collect(),
+    };
+
+    vec![
+        metric(
+            "process_cpu_seconds_total",
+            "Total user and system CPU time spent in seconds.",
+            MetricType::Counter,
+            Some(stats.cpu_seconds),
+        ),
+        metric(
+            "process_resident_memory_bytes",
+            "Resident memory size in bytes.",
+            MetricType::Gauge,
+            stats.resident_bytes.map(|v| v as f64),
+        ),
+        metric(
+            "process_virtual_memory_bytes",
+            "Virtual memory size in bytes.",
+            MetricType::Gauge,
+            Some(stats.virtual_bytes as f64),
+        ),
+        metric(
+            "process_open_fds",
+            "Number of open file descriptors.",
+            MetricType::Gauge,
+            stats.open_fds.map(|v| v as f64),
+        ),
+        metric(
+            "process_max_fds",
+            "Maximum number of open file descriptors.",
+            MetricType::Gauge,
+            stats.max_fds.map(|v| v as f64),
+        ),
+        metric(
+            "process_start_time_seconds",
+            "Start time of the process since unix epoch in seconds.",
+            MetricType::Gauge,
+            Some(stats.start_time_seconds),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc");
+
+    #[test]
+    fn test_parse_limit() {
+        let content = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+";
+        assert_eq!(parse_limit(content, "Max open files"), Some(1024));
+        assert_eq!(parse_limit(content, "Max cpu time"), None);
+        assert_eq!(parse_limit(content, "Max processes"), None);
+    }
+
+    #[test]
+    fn test_read_self_process() {
+        let stats = read_self_process(Path::new(PROC_FIXTURE_DIR)).unwrap();
+        assert_eq!(
+            stats,
+            SelfProcessStats {
+                // (250 + 50) ticks / 100
+                cpu_seconds: 3.0,
+                resident_bytes: Some(12000 * 1024),
+                virtual_bytes: 123456789,
+                open_fds: Some(7),
+                max_fds: Some(1024),
+                // btime + 360000 ticks / 100
+                start_time_seconds: 1700003600.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_self_process_metrics() {
+        let stats = read_self_process(Path::new(PROC_FIXTURE_DIR)).unwrap();
+        let metrics = self_process_metrics(&stats);
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "process_cpu_seconds_total",
+                "process_resident_memory_bytes",
+                "process_virtual_memory_bytes",
+                "process_open_fds",
+                "process_max_fds",
+                "process_start_time_seconds",
+            ]
+        );
+        assert_eq!(metrics[0].metric_type, MetricType::Counter);
+        assert_eq!(metrics[4].samples[0].value, 1024.0);
+    }
+
+    #[test]
+    fn test_self_process_metrics_missing_values() {
+        let stats = SelfProcessStats {
+            cpu_seconds: 1.0,
+            resident_bytes: None,
+            virtual_bytes: 0,
+            open_fds: None,
+            max_fds: None,
+            start_time_seconds: 0.0,
+        };
+        let metrics = self_process_metrics(&stats);
+        assert!(metrics[1].samples.is_empty());
+        assert!(metrics[4].samples.is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::collector::{read_optional, Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const PROC_NET_PATH: &str = "/proc/net";
+
+/// TCP states as numbered in the `st` column (include/net/tcp_states.h).
+const TCP_STATES: &[(u8, &str)] = &[
+    (1, "established"),
+    (2, "syn_sent"),
+    (3, "syn_recv"),
+    (4, "fin_wait1"),
+    (5, "fin_wait2"),
+    (6, "time_wait"),
+    (7, "close"),
+    (8, "close_wait"),
+    (9, "last_ack"),
+    (10, "listen"),
+    (11, "closing"),
+];
+
+/// `st` value of listening sockets.
+const TCP_LISTEN: u8 = 10;
+
+/// A socket line of /proc/net/tcp or tcp6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpSocket {
+    pub local_port: u16,
+    pub state: u8,
+    pub tx_queue: u64,
+    pub rx_queue: u64,
+}
+
+/// Parse /proc/net/tcp or tcp6. All numbers in the file are hexadecimal.
+pub fn parse_proc_net_tcp(content: &str, path: &str) -> Result<Vec<TcpSocket>, CollectorError> {
+    let mut sockets = Vec::new();
+    // The first line is a header.
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let parse_err = |field: &str| CollectorError::Parse {
+            path: path.to_string(),
+            field: field.to_string(),
+            raw: line.to_string(),
+        };
+        if fields.len() < 5 {
+            return Err(parse_err("socket line"));
+        }
+        let local_port = fields[1]
+            .rsplit_once(':')
+            .and_then(|(_, port)| u16::from_str_radix(port, 16).ok())
+            .ok_or_else(|| parse_err("local_address"))?;
+        let state = u8::from_str_radix(fields[3], 16).map_err(|_| parse_err("st"))?;
+        let (tx_queue, rx_queue) = fields[4]
+            .split_once(':')
+            .and_then(|(tx, rx)| {
+                Some((
+                    u64::from_str_radix(tx, 16).ok()?,
+                    u64::from_str_radix(rx, 16).ok()?,
+                ))
+            })
+            .ok_or_else(|| parse_err("tx_queue:rx_queue"))?;
+        sockets.push(TcpSocket {
+            local_port,
+            state,
+            tx_queue,
+            rx_queue,
+        });
+    }
+    Ok(sockets)
+}
+
+/// Count sockets per `st` value, with every known state present.
+fn count_states<'a>(sockets: impl Iterator<Item = &'a TcpSocket>) -> BTreeMap<u8, u64> {
+    let mut counts: BTreeMap<u8, u64> = TCP_STATES.iter().map(|(st, _)| (*st, 0)).collect();
+    for socket in sockets {
+        *counts.entry(socket.state).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn state_name(state: u8) -> String {
+    TCP_STATES
+        .iter()
+        .find(|(st, _)| *st == state)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("unknown_{}", state))
+}
+
+pub struct TcpstatCollector {
+    ports: Vec<u16>,
+}
+
+impl TcpstatCollector {
+    /// `ports` are the local ports that get a per-port state breakdown.
+    pub fn new(ports: Vec<u16>) -> Self {
+        Self { ports }
+    }
+}
+
+impl Collector for TcpstatCollector {
+    fn name(&self) -> &'static str {
+        "tcpstat"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(PROC_NET_PATH))
+    }
+}
+
+impl TcpstatCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        let mut sockets = Vec::new();
+        let mut found = false;
+        for file in ["tcp", "tcp6"] {
+            let path = root.join(file);
+            if let Some(content) = read_optional(&path)? {
+                found = true;
+                sockets.extend(parse_proc_net_tcp(&content, &path.display().to_string())?);
+            }
+        }
+        if !found {
+            return Err(CollectorError::NotSupported {
+                path: root.join("tcp").display().to_string(),
+            });
+        }
+
+        let state_samples = count_states(sockets.iter())
+            .into_iter()
+            .map(|(state, count)| MetricSample {
+                labels: vec![("state".to_string(), state_name(state))],
+                value: count as f64,
+            })
+            .collect();
+
+        let port_samples = self
+            .ports
+            .iter()
+            .flat_map(|port| {
+                count_states(sockets.iter().filter(|s| s.local_port == *port))
+                    .into_iter()
+                    .map(move |(state, count)| MetricSample {
+                        labels: vec![
+                            ("port".to_string(), port.to_string()),
+                            ("state".to_string(), state_name(state)),
+                        ],
+                        value: count as f64,
+                    })
+            })
+            .collect();
+
+        // For listening sockets the kernel reports the accept backlog in
+        // connections rather than bytes, so they are left out of the sums.
+        let queue_sample = |value_fn: fn(&TcpSocket) -> u64| {
+            vec![MetricSample {
+                labels: vec![],
+                value: sockets
+                    .iter()
+                    .filter(|s| s.state != TCP_LISTEN)
+                    .map(value_fn)
+                    .sum::<u64>() as f64,
+            }]
+        };
+
+        let mut metrics = vec![
+            Metric {
+                name: "sysmetrics_tcp_connection_states".to_string(),
+                help: "Number of IPv4 and IPv6 TCP sockets in each state.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: state_samples,
+            },
+            Metric {
+                name: "sysmetrics_tcp_transmit_queue_bytes".to_string(),
+                help: "Bytes in the send queues of all non-listening TCP sockets.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: queue_sample(|s| s.tx_queue),
+            },
+            Metric {
+                name: "sysmetrics_tcp_receive_queue_bytes".to_string(),
+                help: "Bytes in the receive queues of all non-listening TCP sockets.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: queue_sample(|s| s.rx_queue),
+            },
+        ];
+        if !self.ports.is_empty() {
+            metrics.push(Metric {
+                name: "sysmetrics_tcp_port_connection_states".to_string(),
+                help: "Number of TCP sockets in each state for a configured local port."
+                    .to_string(),
+                metric_type: MetricType::Gauge,
+                samples: port_samples,
+            });
+        }
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::assert_missing_root_not_supported;
+
+    const NET_FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc/net");
+
+    fn state_value(metric: &Metric, labels: &[(&str, &str)]) -> f64 {
+        let labels: Vec<(String, String)> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        metric
+            .samples
+            .iter()
+            .find(|s| s.labels == labels)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_parse_proc_net_tcp() {
+        let content = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000003 00:00000000 00000000     0        0 18745 1
+   1: 0A00020F:0016 0A000202:D2F4 01 00000024:00000000 01:00000014 00000000     0        0 30123 4
+";
+        let sockets = parse_proc_net_tcp(content, "/proc/net/tcp").unwrap();
+        assert_eq!(
+            sockets,
+            vec![
+                TcpSocket {
+                    local_port: 22,
+                    state: 10,
+                    tx_queue: 0,
+                    rx_queue: 3,
+                },
+                TcpSocket {
+                    local_port: 22,
+                    state: 1,
+                    tx_queue: 36,
+                    rx_queue: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_proc_net_tcp_malformed() {
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue\n";
+        let bad_port = format!("{}   0: 00000000:XYZ 00000000:0000 0A 0:0\n", header);
+        assert!(parse_proc_net_tcp(&bad_port, "tcp").is_err());
+        let short = format!("{}   0: 00000000:0016\n", header);
+        assert!(parse_proc_net_tcp(&short, "tcp").is_err());
+    }
+
+    #[test]
+    fn test_tcpstat_collector_states() {
+        let metrics = TcpstatCollector::new(vec![])
+            .collect_from_dir(Path::new(NET_FIXTURE_DIR))
+            .unwrap();
+        // No per-port metric without configured ports.
+        assert_eq!(metrics.len(), 3);
+
+        let states = &metrics[0];
+        assert_eq!(states.samples.len(), TCP_STATES.len());
+        // tcp and tcp6 are summed.
+        assert_eq!(state_value(states, &[("state", "established")]), 3.0);
+        assert_eq!(state_value(states, &[("state", "listen")]), 3.0);
+        assert_eq!(state_value(states, &[("state", "time_wait")]), 1.0);
+        assert_eq!(state_value(states, &[("state", "close_wait")]), 1.0);
+        assert_eq!(state_value(states, &[("state", "syn_sent")]), 0.0);
+
+        assert_eq!(metrics[1].name, "sysmetrics_tcp_transmit_queue_bytes");
+        assert_eq!(metrics[1].samples[0].value, 36.0 + 256.0);
+        // The backlog of 3 on a listening socket is not bytes.
+        assert_eq!(metrics[2].samples[0].value, 16.0 + 1.0);
+    }
+
+    #[test]
+    fn test_tcpstat_collector_ports() {
+        let metrics = TcpstatCollector::new(vec![22, 80, 8080])
+            .collect_from_dir(Path::new(NET_FIXTURE_DIR))
+            .unwrap();
+        let ports = &metrics[3];
+        assert_eq!(ports.name, "sysmetrics_tcp_port_connection_states");
+        assert_eq!(ports.samples.len(), 3 * TCP_STATES.len());
+        assert_eq!(
+            state_value(ports, &[("port", "22"), ("state", "established")]),
+            2.0
+        );
+        assert_eq!(
+            state_value(ports, &[("port", "80"), ("state", "listen")]),
+            2.0
+        );
+        assert_eq!(
+            state_value(ports, &[("port", "80"), ("state", "established")]),
+            1.0
+        );
+        assert_eq!(
+            state_value(ports, &[("port", "8080"), ("state", "listen")]),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_tcpstat_collector_missing_root() {
+        assert_missing_root_not_supported(|root| {
+            TcpstatCollector::new(vec![]).collect_from_dir(root)
+        });
+    }
+}
@@ -0,0 +1,252 @@
+use crate::collector::{
+    list_sysfs_dir, read_sysfs_string, read_sysfs_value, Collector, Metric, MetricSample,
+    MetricType,
+};
+use crate::error::CollectorError;
+use std::path::Path;
+
+const SYS_CLASS_THERMAL_PATH: &str = "/sys/class/thermal";
+const MILLIDEGREES_PER_DEGREE: f64 = 1000.0;
+
+/// A thermal zone with its current temperature and trip points, all in
+/// millidegrees celsius as reported by sysfs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalZone {
+    pub zone: String,
+    pub zone_type: String,
+    pub temp: Option<i64>,
+    /// (trip point index, trip type, temperature)
+    pub trip_points: Vec<(String, String, i64)>,
+}
+
+/// A cooling device (fan, CPU frequency clamp, ...) and its throttle state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoolingDevice {
+    pub device: String,
+    pub device_type: String,
+    pub cur_state: i64,
+    pub max_state: i64,
+}
+
+/// Strip `prefix` from the final path component, e.g. `thermal_zone3` -> `3`.
+fn sysfs_index(path: &Path, prefix: &str) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.strip_prefix(prefix))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Read a single `thermal_zoneN` directory.
+pub fn read_thermal_zone(dir: &Path) -> ThermalZone {
+    let mut trip_points = Vec::new();
+    for index in 0.. {
+        let Some(temp) = read_sysfs_value::<i64>(&dir.join(format!("trip_point_{}_temp", index)))
+        else {
+            break;
+        };
+        let trip_type =
+            read_sysfs_string(&dir.join(format!("trip_point_{}_type", index))).unwrap_or_default();
+        trip_points.push((index.to_string(), trip_type, temp));
+    }
+    ThermalZone {
+        zone: sysfs_index(dir, "thermal_zone"),
+        zone_type: read_sysfs_string(&dir.join("type")).unwrap_or_default(),
+        // Some zones (e.g. wireless adapters that are powered down) fail
+        // reads of `temp`; they are still listed with their trip points.
+        temp: read_sysfs_value(&dir.join("temp")),
+        trip_points,
+    }
+}
+
+/// Read a single `cooling_deviceN` directory. Returns `None` if the state
+/// files cannot be read.
+pub fn read_cooling_device(dir: &Path) -> Option<CoolingDevice> {
+    Some(CoolingDevice {
+        device: sysfs_index(dir, "cooling_device"),
+        device_type: read_sysfs_string(&dir.join("type")).unwrap_or_default(),
+        cur_state: read_sysfs_value(&dir.join("cur_state"))?,
+        max_state: read_sysfs_value(&dir.join("max_state"))?,
+    })
+}
+
+pub struct ThermalCollector;
+
+impl Collector for ThermalCollector {
+    fn name(&self) -> &'static str {
+        "thermal"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(SYS_CLASS_THERMAL_PATH))
+    }
+}
+
+impl ThermalCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        let zones: Vec<ThermalZone> = list_sysfs_dir(root, "thermal_zone")?
+            .iter()
+            .map(|dir| read_thermal_zone(dir))
+            .collect();
+        let devices: Vec<CoolingDevice> = list_sysfs_dir(root, "cooling_device")?
+            .iter()
+            .filter_map(|dir| read_cooling_device(dir))
+            .collect();
+
+        let zone_labels = |z: &ThermalZone| {
+            vec![
+                ("zone".to_string(), z.zone.clone()),
+                ("type".to_string(), z.zone_type.clone()),
+            ]
+        };
+        let device_labels = |d: &CoolingDevice| {
+            vec![
+                ("name".to_string(), d.device.clone()),
+                ("type".to_string(), d.device_type.clone()),
+            ]
+        };
+
+        let temp_samples = zones
+            .iter()
+            .filter_map(|z| {
+                z.temp.map(|temp| MetricSample {
+                    labels: zone_labels(z),
+                    value: temp as f64 / MILLIDEGREES_PER_DEGREE,
+                })
+            })
+            .collect();
+
+        let trip_samples = zones
+            .iter()
+            .flat_map(|z| {
+                z.trip_points.iter().map(move |(index, trip_type, temp)| {
+                    let mut labels = zone_labels(z);
+                    labels.push(("trip_point".to_string(), index.clone()));
+                    labels.push(("trip_type".to_string(), trip_type.clone()));
+                    MetricSample {
+                        labels,
+                        value: *temp as f64 / MILLIDEGREES_PER_DEGREE,
+                    }
+                })
+            })
+            .collect();
+
+        Ok(vec![
+            Metric {
+                name: "sysmetrics_thermal_zone_temp_celsius".to_string(),
+                help: "Thermal zone temperature in degrees celsius.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: temp_samples,
+            },
+            Metric {
+                name: "sysmetrics_thermal_zone_trip_point_celsius".to_string(),
+                help: "Thermal zone trip point temperature in degrees celsius.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: trip_samples,
+            },
+            Metric {
+                name: "sysmetrics_cooling_device_cur_state".to_string(),
+                help: "Current throttle state of the cooling device.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: devices
+                    .iter()
+                    .map(|d| MetricSample {
+                        labels: device_labels(d),
+                        value: d.cur_state as f64,
+                    })
+                    .collect(),
+            },
+            Metric {
+                name: "sysmetrics_cooling_device_max_state".to_string(),
+                help: "Maximum throttle state of the cooling device.".to_string(),
+                metric_type: MetricType::Gauge,
+                samples: devices
+                    .iter()
+                    .map(|d| MetricSample {
+                        labels: device_labels(d),
+                        value: d.max_state as f64,
+                    })
+                    .collect(),
+            },
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::assert_missing_root_not_supported;
+
+    const THERMAL_FIXTURE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/sys/class/thermal"
+    );
+
+    #[test]
+    fn test_read_thermal_zone() {
+        let zone = read_thermal_zone(&Path::new(THERMAL_FIXTURE_DIR).join("thermal_zone0"));
+        assert_eq!(
+            zone,
+            ThermalZone {
+                zone: "0".to_string(),
+                zone_type: "acpitz".to_string(),
+                temp: Some(27800),
+                trip_points: vec![
+                    ("0".to_string(), "critical".to_string(), 105000),
+                    ("1".to_string(), "passive".to_string(), 95000),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_cooling_device() {
+        let device =
+            read_cooling_device(&Path::new(THERMAL_FIXTURE_DIR).join("cooling_device1")).unwrap();
+        assert_eq!(device.device, "1");
+        assert_eq!(device.device_type, "intel_powerclamp");
+        assert_eq!(device.cur_state, -1);
+        assert_eq!(device.max_state, 50);
+    }
+
+    #[test]
+    fn test_thermal_collector_metrics() {
+        let metrics = ThermalCollector
+            .collect_from_dir(Path::new(THERMAL_FIXTURE_DIR))
+            .unwrap();
+        assert_eq!(metrics.len(), 4);
+
+        let temps = &metrics[0];
+        assert_eq!(temps.name, "sysmetrics_thermal_zone_temp_celsius");
+        // thermal_zone2 has an unreadable temp and is skipped.
+        assert_eq!(temps.samples.len(), 2);
+        assert_eq!(
+            temps.samples[1].labels,
+            vec![
+                ("zone".to_string(), "1".to_string()),
+                ("type".to_string(), "x86_pkg_temp".to_string()),
+            ]
+        );
+        assert_eq!(temps.samples[1].value, 45.0);
+
+        let trips = &metrics[1];
+        assert_eq!(trips.samples.len(), 3);
+        assert_eq!(trips.samples[0].labels[2].1, "0");
+        assert_eq!(trips.samples[0].labels[3].1, "critical");
+        assert_eq!(trips.samples[0].value, 105.0);
+        // Trip points of a zone with an unreadable temp are still reported.
+        assert_eq!(trips.samples[2].labels[1].1, "iwlwifi_1");
+
+        let cur = &metrics[2];
+        assert_eq!(cur.name, "sysmetrics_cooling_device_cur_state");
+        assert_eq!(cur.samples.len(), 2);
+        assert_eq!(cur.samples[0].value, 3.0);
+        let max = &metrics[3];
+        assert_eq!(max.samples[0].value, 10.0);
+    }
+
+    #[test]
+    fn test_thermal_collector_missing_root() {
+        assert_missing_root_not_supported(|root| ThermalCollector.collect_from_dir(root));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::collector::{Collector, Metric, MetricSample, MetricType};
+use crate::error::CollectorError;
+use regex::Regex;
+use std::fs;
+
+const PROC_VMSTAT_PATH: &str = "/proc/vmstat";
+
+/// `nr_*` fields that are cumulative event counts rather than current values.
+const NR_COUNTER_FIELDS: &[&str] = &[
+    "nr_dirtied",
+    "nr_written",
+    "nr_throttled_written",
+    "nr_vmscan_write",
+    "nr_vmscan_immediate_reclaim",
+    "nr_foll_pin_acquired",
+    "nr_foll_pin_released",
+];
+
+/// Fields without the `nr_` prefix that report a current value.
+const GAUGE_FIELDS: &[&str] = &["workingset_nodes"];
+
+/// Parse /proc/vmstat content into (key, value) pairs, preserving file order.
+pub fn parse_vmstat(content: &str) -> Result<Vec<(String, u64)>, CollectorError> {
+    let mut fields = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, raw)) = line.split_once(' ') else {
+            return Err(CollectorError::Parse {
+                path: PROC_VMSTAT_PATH.to_string(),
+                field: "vmstat line".to_string(),
+                raw: line.to_string(),
+            });
+        };
+        let raw = raw.trim();
+        let value = raw.parse::<u64>().map_err(|_| CollectorError::Parse {
+            path: PROC_VMSTAT_PATH.to_string(),
+            field: key.to_string(),
+            raw: raw.to_string(),
+        })?;
+        fields.push((key.to_string(), value));
+    }
+    Ok(fields)
+}
+
+/// Type of a /proc/vmstat field. Most `nr_*` fields are current page counts;
+/// everything else is a monotonically increasing event counter.
+pub fn vmstat_metric_type(key: &str) -> MetricType {
+    if GAUGE_FIELDS.contains(&key) || (key.starts_with("nr_") && !NR_COUNTER_FIELDS.contains(&key))
+    {
+        MetricType::Gauge
+    } else {
+        MetricType::Counter
+    }
+}
+
+pub struct VmstatCollector {
+    include_pattern: Regex,
+}
+
+impl VmstatCollector {
+    pub fn new(include_pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            include_pattern: Regex::new(include_pattern)?,
+        })
+    }
+}
+
+impl Collector for VmstatCollector {
+    fn name(&self) -> &'static str {
+        "vmstat"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        let content =
+            fs::read_to_string(PROC_VMSTAT_PATH).map_err(|e| CollectorError::FileRead {
+                path: PROC_VMSTAT_PATH.to_string(),
+                source: e,
+            })?;
+        self.collect_from_string(&content)
+    }
+}
+
+impl VmstatCollector {
+    pub fn collect_from_string(&self, content: &str) -> Result<Vec<Metric>, CollectorError> {
+        Ok(parse_vmstat(content)?
+            .into_iter()
+            .filter(|(key, _)| self.include_pattern.is_match(key))
+            .map(|(key, value)| {
+                let metric_type = vmstat_metric_type(&key);
+                let name = match metric_type {
+                    MetricType::Counter => format!("sysmetrics_vmstat_{}_total", key),
+                    MetricType::Gauge => format!("sysmetrics_vmstat_{}", key),
+                };
+                Metric {
+                    name,
+                    help: format!("/proc/vmstat field {}.", key),
+                    metric_type,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value: value as f64,
+                    }],
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VMSTAT_FIXTURE: &str = "\
+nr_free_pages 569568
+nr_dirty 77786
+nr_dirtied 780612
+workingset_nodes 12
+pgpgin 1234567
+pgpgout 7654321
+pswpin 10
+pswpout 20
+pgfault 99887766
+pgmajfault 4321
+oom_kill 3
+compact_stall 5
+";
+
+    #[test]
+    fn test_parse_vmstat() {
+        let fields = parse_vmstat(VMSTAT_FIXTURE).unwrap();
+        assert_eq!(fields.len(), 12);
+        assert_eq!(fields[0], ("nr_free_pages".to_string(), 569568));
+        assert_eq!(fields[10], ("oom_kill".to_string(), 3));
+    }
+
+    #[test]
+    fn test_parse_vmstat_malformed() {
+        assert!(parse_vmstat("pgfault abc\n").is_err());
+        assert!(parse_vmstat("pgfault\n").is_err());
+    }
+
+    #[test]
+    fn test_vmstat_metric_type() {
+        assert_eq!(vmstat_metric_type("nr_free_pages"), MetricType::Gauge);
+        assert_eq!(vmstat_metric_type("nr_dirtied"), MetricType::Counter);
+        assert_eq!(vmstat_metric_type("workingset_nodes"), MetricType::Gauge);
+        assert_eq!(vmstat_metric_type("pgfault"), MetricType::Counter);
+        assert_eq!(vmstat_metric_type("oom_kill"), MetricType::Counter);
+    }
+
+    #[test]
+    fn test_vmstat_collector_default_allowlist() {
+        let collector = VmstatCollector::new("^(pgfault|pgmajfault|pswp|oom_kill|pgpg)").unwrap();
+        let metrics = collector.collect_from_string(VMSTAT_FIXTURE).unwrap();
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "sysmetrics_vmstat_pgpgin_total",
+                "sysmetrics_vmstat_pgpgout_total",
+                "sysmetrics_vmstat_pswpin_total",
+                "sysmetrics_vmstat_pswpout_total",
+                "sysmetrics_vmstat_pgfault_total",
+                "sysmetrics_vmstat_pgmajfault_total",
+                "sysmetrics_vmstat_oom_kill_total",
+            ]
+        );
+        assert!(metrics.iter().all(|m| m.metric_type == MetricType::Counter));
+        assert_eq!(metrics[4].samples[0].value, 99887766.0);
+    }
+
+    #[test]
+    fn test_vmstat_collector_gauges() {
+        let collector = VmstatCollector::new("^nr_").unwrap();
+        let metrics = collector.collect_from_string(VMSTAT_FIXTURE).unwrap();
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].name, "sysmetrics_vmstat_nr_free_pages");
+        assert_eq!(metrics[0].metric_type, MetricType::Gauge);
+        assert_eq!(metrics[2].name, "sysmetrics_vmstat_nr_dirtied_total");
+        assert_eq!(metrics[2].metric_type, MetricType::Counter);
+    }
+}
@@ -0,0 +1,582 @@
+use crate::collector::{
+    list_sysfs_dir, read_optional, read_sysfs_string, Collector, Metric, MetricSample, MetricType,
+};
+use crate::error::CollectorError;
+use std::path::Path;
+
+const PROC_ZFS_KSTAT_PATH: &str = "/proc/spl/kstat/zfs";
+
+/// kstat types from the second field of the header line.
+const KSTAT_TYPE_NAMED: u8 = 1;
+const KSTAT_TYPE_IO: u8 = 3;
+
+/// Data types of named kstat rows. Char and string rows are skipped.
+const KSTAT_DATA_INT32: u8 = 1;
+const KSTAT_DATA_UINT32: u8 = 2;
+const KSTAT_DATA_INT64: u8 = 3;
+const KSTAT_DATA_UINT64: u8 = 4;
+const KSTAT_DATA_LONG: u8 = 5;
+const KSTAT_DATA_ULONG: u8 = 6;
+
+/// arcstats rows that are counters besides the `*hits`, `*misses` and
+/// `evict_*` rows. Everything else in arcstats is a size, limit or other
+/// current value, so rows added by newer OpenZFS versions default to gauges.
+const ARCSTATS_COUNTERS: &[&str] = &[
+    "deleted",
+    "mutex_miss",
+    "access_skip",
+    "hash_collisions",
+    "l2_feeds",
+    "l2_rw_clash",
+    "l2_read_bytes",
+    "l2_write_bytes",
+    "l2_writes_sent",
+    "l2_writes_done",
+    "l2_writes_error",
+    "l2_writes_lock_retry",
+    "l2_evict_lock_retry",
+    "l2_evict_reading",
+    "l2_evict_l1cached",
+    "l2_free_on_write",
+    "l2_abort_lowmem",
+    "l2_cksum_bad",
+    "l2_io_error",
+    "l2_log_blk_writes",
+    "l2_rebuild_success",
+    "l2_rebuild_unsupported",
+    "l2_rebuild_io_errors",
+    "l2_rebuild_dh_errors",
+    "l2_rebuild_cksum_lb_errors",
+    "l2_rebuild_lowmem",
+    "l2_rebuild_bufs",
+    "l2_rebuild_bufs_precached",
+    "l2_rebuild_log_blks",
+    "memory_throttle_count",
+    "memory_direct_count",
+    "memory_indirect_count",
+    "arc_prune",
+    "async_upgrade_sync",
+    "predictive_prefetch",
+    "prescient_prefetch",
+    "demand_hit_predictive_prefetch",
+    "demand_iohit_predictive_prefetch",
+    "demand_hit_prescient_prefetch",
+    "demand_iohit_prescient_prefetch",
+];
+
+fn is_arcstats_counter(name: &str) -> bool {
+    name.ends_with("hits")
+        || name.ends_with("misses")
+        || name.starts_with("evict_")
+        || ARCSTATS_COUNTERS.contains(&name)
+}
+
+/// Whether an unsigned row of a kstat file is a counter.
+type IsCounter = fn(&str) -> bool;
+
+/// Global kstat files: file name, metric prefix, prefix repeated in the row
+/// names, and which unsigned rows are counters.
+const KSTAT_FILES: &[(&str, &str, &str, IsCounter)] = &[
+    ("arcstats", "sysmetrics_zfs_arc_", "", is_arcstats_counter),
+    // Every zil and dmu_tx row counts events or bytes.
+    ("zil", "sysmetrics_zfs_zil_", "zil_", |_| true),
+    ("dmu_tx", "sysmetrics_zfs_dmu_tx_", "dmu_tx_", |_| true),
+];
+
+/// Columns of the per-pool `io` kstat: column, metric name suffix, type,
+/// factor to the exported unit, and help. Times are kept in nanoseconds
+/// by the kernel; the `wupdate` and `rupdate` timestamps are not exported.
+const POOL_IO_COLUMNS: &[(&str, &str, MetricType, f64, &str)] = &[
+    (
+        "nread",
+        "read_bytes_total",
+        MetricType::Counter,
+        1.0,
+        "Bytes read from the pool.",
+    ),
+    (
+        "nwritten",
+        "written_bytes_total",
+        MetricType::Counter,
+        1.0,
+        "Bytes written to the pool.",
+    ),
+    (
+        "reads",
+        "reads_total",
+        MetricType::Counter,
+        1.0,
+        "Read operations on the pool.",
+    ),
+    (
+        "writes",
+        "writes_total",
+        MetricType::Counter,
+        1.0,
+        "Write operations on the pool.",
+    ),
+    (
+        "wtime",
+        "wtime_seconds_total",
+        MetricType::Counter,
+        1e-9,
+        "Time the pool's wait queue was non-empty.",
+    ),
+    (
+        "wlentime",
+        "wlentime_seconds_total",
+        MetricType::Counter,
+        1e-9,
+        "Wait queue length integrated over time.",
+    ),
+    (
+        "rtime",
+        "rtime_seconds_total",
+        MetricType::Counter,
+        1e-9,
+        "Time the pool's run queue was non-empty.",
+    ),
+    (
+        "rlentime",
+        "rlentime_seconds_total",
+        MetricType::Counter,
+        1e-9,
+        "Run queue length integrated over time.",
+    ),
+    (
+        "wcnt",
+        "wcnt",
+        MetricType::Gauge,
+        1.0,
+        "Operations in the pool's wait queue.",
+    ),
+    (
+        "rcnt",
+        "rcnt",
+        MetricType::Gauge,
+        1.0,
+        "Operations in the pool's run queue.",
+    ),
+];
+
+/// Pool states exported for every pool.
+const POOL_STATES: &[&str] = &[
+    "online",
+    "degraded",
+    "faulted",
+    "offline",
+    "removed",
+    "unavail",
+    "suspended",
+];
+
+/// A numeric row of a kstat file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KstatRow {
+    pub name: String,
+    pub value: f64,
+    /// Signed rows can go down and are always gauges.
+    pub signed: bool,
+}
+
+/// Parse a kstat file. The first line is the kstat header, whose second
+/// field is the kstat type:
+///
+/// ```text
+/// 13 1 0x01 123 33456 6140375925 1836537702153655
+/// name                            type data
+/// hits                            4    98765432
+/// ```
+///
+/// Named kstats (type 1) have one `name type data` row per value; I/O
+/// kstats (type 3) have a line of column names followed by one line of
+/// values.
+pub fn parse_kstat(content: &str, path: &str) -> Result<Vec<KstatRow>, CollectorError> {
+    let parse_err = |field: &str, raw: &str| CollectorError::Parse {
+        path: path.to_string(),
+        field: field.to_string(),
+        raw: raw.to_string(),
+    };
+    let mut lines = content.lines();
+    let header = lines.next().unwrap_or_default();
+    let kstat_type = header
+        .split_whitespace()
+        .nth(1)
+        .and_then(|t| t.parse::<u8>().ok())
+        .ok_or_else(|| parse_err("header", header))?;
+
+    let mut rows = Vec::new();
+    match kstat_type {
+        KSTAT_TYPE_NAMED => {
+            // Skip the `name type data` column header.
+            for line in lines.skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.is_empty() {
+                    continue;
+                }
+                if fields.len() < 3 {
+                    return Err(parse_err("row", line));
+                }
+                let data_type: u8 = fields[1].parse().map_err(|_| parse_err(fields[0], line))?;
+                let signed = match data_type {
+                    KSTAT_DATA_INT32 | KSTAT_DATA_INT64 | KSTAT_DATA_LONG => true,
+                    KSTAT_DATA_UINT32 | KSTAT_DATA_UINT64 | KSTAT_DATA_ULONG => false,
+                    _ => continue,
+                };
+                let value: f64 = fields[2].parse().map_err(|_| parse_err(fields[0], line))?;
+                rows.push(KstatRow {
+                    name: fields[0].to_string(),
+                    value,
+                    signed,
+                });
+            }
+        }
+        KSTAT_TYPE_IO => {
+            let names = lines.next().unwrap_or_default();
+            let values = lines.next().unwrap_or_default();
+            let names: Vec<&str> = names.split_whitespace().collect();
+            let values: Vec<&str> = values.split_whitespace().collect();
+            if names.is_empty() || names.len() != values.len() {
+                return Err(parse_err("io columns", content));
+            }
+            for (name, raw) in names.into_iter().zip(values) {
+                rows.push(KstatRow {
+                    name: name.to_string(),
+                    value: raw.parse().map_err(|_| parse_err(name, raw))?,
+                    signed: false,
+                });
+            }
+        }
+        _ => return Err(parse_err("kstat type", header)),
+    }
+    Ok(rows)
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Metric name and type of a kstat row: counters get a `_total` suffix,
+/// gauges keep the row name. Signed rows are always gauges.
+fn kstat_metric(prefix: &str, row: &KstatRow, is_counter: IsCounter) -> (String, MetricType) {
+    if !row.signed && is_counter(&row.name) {
+        (
+            format!("{}{}_total", prefix, sanitize(&row.name)),
+            MetricType::Counter,
+        )
+    } else {
+        (
+            format!("{}{}", prefix, sanitize(&row.name)),
+            MetricType::Gauge,
+        )
+    }
+}
+
+pub struct ZfsCollector;
+
+impl Collector for ZfsCollector {
+    fn name(&self) -> &'static str {
+        "zfs"
+    }
+
+    fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+        self.collect_from_dir(Path::new(PROC_ZFS_KSTAT_PATH))
+    }
+}
+
+impl ZfsCollector {
+    pub fn collect_from_dir(&self, root: &Path) -> Result<Vec<Metric>, CollectorError> {
+        // Each directory is a pool; a missing root means ZFS is not loaded.
+        let pools: Vec<_> = list_sysfs_dir(root, "")?
+            .into_iter()
+            .filter(|p| p.is_dir())
+            .collect();
+
+        let mut metrics = Vec::new();
+        for (file, prefix, row_prefix, is_counter) in KSTAT_FILES {
+            let path = root.join(file);
+            let Some(content) = read_optional(&path)? else {
+                continue;
+            };
+            for row in parse_kstat(&content, &path.display().to_string())? {
+                let row = KstatRow {
+                    name: row
+                        .name
+                        .strip_prefix(row_prefix)
+                        .unwrap_or(&row.name)
+                        .to_string(),
+                    ..row
+                };
+                let (name, metric_type) = kstat_metric(prefix, &row, *is_counter);
+                metrics.push(Metric {
+                    name,
+                    help: format!("ZFS kstat {} from {}.", row.name, file),
+                    metric_type,
+                    samples: vec![MetricSample {
+                        labels: vec![],
+                        value: row.value,
+                    }],
+                });
+            }
+        }
+
+        // Pool io columns are the same for every pool, so each column is one
+        // metric family with a sample per pool.
+        let mut pool_io: Vec<Metric> = Vec::new();
+        let mut state_samples = Vec::new();
+        for pool_dir in &pools {
+            let pool = pool_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let io_path = pool_dir.join("io");
+            // The io kstat was removed in OpenZFS 2.1.
+            if let Some(content) = read_optional(&io_path)? {
+                for row in parse_kstat(&content, &io_path.display().to_string())? {
+                    let Some((_, suffix, metric_type, scale, help)) =
+                        POOL_IO_COLUMNS.iter().find(|c| c.0 == row.name)
+                    else {
+                        continue;
+                    };
+                    let name = format!("sysmetrics_zfs_pool_io_{}", suffix);
+                    let sample = MetricSample {
+                        labels: vec![("pool".to_string(), pool.clone())],
+                        value: row.value * scale,
+                    };
+                    match pool_io.iter_mut().find(|m| m.name == name) {
+                        Some(metric) => metric.samples.push(sample),
+                        None => pool_io.push(Metric {
+                            name,
+                            help: help.to_string(),
+                            metric_type: *metric_type,
+                            samples: vec![sample],
+                        }),
+                    }
+                }
+            }
+            if let Some(state) = read_sysfs_string(&pool_dir.join("state")) {
+                let state = state.to_lowercase();
+                state_samples.extend(POOL_STATES.iter().map(|s| MetricSample {
+                    labels: vec![
+                        ("pool".to_string(), pool.clone()),
+                        ("state".to_string(), s.to_string()),
+                    ],
+                    value: if state == *s { 1.0 } else { 0.0 },
+                }));
+            }
+        }
+        metrics.extend(pool_io);
+        metrics.push(Metric {
+            name: "sysmetrics_zfs_pool_state".to_string(),
+            help: "Whether a ZFS pool is in the given state.".to_string(),
+            metric_type: MetricType::Gauge,
+            samples: state_samples,
+        });
+        Ok(metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::{assert_missing_root_not_supported, find_metric};
+
+    const ZFS_FIXTURE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/proc/spl/kstat/zfs"
+    );
+
+    const ARCSTATS_2_2: &str = include_str!("../../tests/fixtures/zfs/arcstats_2.2.txt");
+
+    #[test]
+    fn test_parse_kstat_named() {
+        let content = "\
+13 1 0x01 3 144 6140375925 1836537702153655
+name                            type data
+hits                            4    98765432
+memory_available_bytes          3    -4096
+class                           7    zfs
+";
+        let rows = parse_kstat(content, "arcstats").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                KstatRow {
+                    name: "hits".to_string(),
+                    value: 98765432.0,
+                    signed: false,
+                },
+                // Signed rows can be negative.
+                KstatRow {
+                    name: "memory_available_bytes".to_string(),
+                    value: -4096.0,
+                    signed: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_kstat_io() {
+        let content = "\
+19 3 0x00 1 80 6140376547 1836537715123456
+nread    nwritten   reads      writes     wcnt       rcnt
+1024     2048       10         20         0          2
+";
+        let rows = parse_kstat(content, "tank/io").unwrap();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[1].name, "nwritten");
+        assert_eq!(rows[1].value, 2048.0);
+        assert_eq!(rows[5].value, 2.0);
+    }
+
+    #[test]
+    fn test_parse_kstat_malformed() {
+        assert!(parse_kstat("", "empty").is_err());
+        assert!(parse_kstat("13 9 0x01\n", "unknown type").is_err());
+        let bad_value = "13 1 0x01\nname type data\nhits 4 many\n";
+        assert!(parse_kstat(bad_value, "arcstats").is_err());
+        let short_io = "19 3 0x00\nnread nwritten\n1024\n";
+        assert!(parse_kstat(short_io, "io").is_err());
+    }
+
+    #[test]
+    fn test_zfs_collector_global_kstats() {
+        let metrics = ZfsCollector
+            .collect_from_dir(Path::new(ZFS_FIXTURE_DIR))
+            .unwrap();
+
+        let hits = find_metric(&metrics, "sysmetrics_zfs_arc_hits_total");
+        assert_eq!(hits.metric_type, MetricType::Counter);
+        assert_eq!(hits.samples[0].value, 98765432.0);
+        let size = find_metric(&metrics, "sysmetrics_zfs_arc_size");
+        assert_eq!(size.metric_type, MetricType::Gauge);
+        assert_eq!(size.samples[0].value, 8451321856.0);
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_zfs_arc_memory_available_bytes").metric_type,
+            MetricType::Gauge
+        );
+
+        // The file name is not repeated in zil and dmu_tx metric names.
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_zfs_zil_commit_count_total").samples[0].value,
+            123456.0
+        );
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_zfs_dmu_tx_assigned_total").samples[0].value,
+            3456789.0
+        );
+    }
+
+    #[test]
+    fn test_arcstats_metric_types() {
+        let rows = parse_kstat(ARCSTATS_2_2, "arcstats").unwrap();
+        let metric_type = |name: &str| {
+            let row = rows.iter().find(|r| r.name == name).unwrap();
+            kstat_metric("sysmetrics_zfs_arc_", row, is_arcstats_counter)
+        };
+
+        for name in [
+            "pd",
+            "pm",
+            "meta",
+            "compressed_size",
+            "uncompressed_size",
+            "overhead_size",
+            "mru_ghost_size",
+            "mfu_ghost_size",
+            "mru_evictable_data",
+            "mfu_ghost_evictable_metadata",
+            "arc_dnode_limit",
+            "abd_chunk_waste_size",
+            "hash_chain_max",
+            "l2_log_blk_count",
+            "memory_available_bytes",
+        ] {
+            assert_eq!(
+                metric_type(name),
+                (format!("sysmetrics_zfs_arc_{}", name), MetricType::Gauge),
+                "{}",
+                name
+            );
+        }
+        for name in [
+            "hits",
+            "iohits",
+            "prefetch_metadata_misses",
+            "mfu_ghost_hits",
+            "evict_l2_eligible_mru",
+            "mutex_miss",
+            "memory_indirect_count",
+            "demand_iohit_prescient_prefetch",
+        ] {
+            assert_eq!(
+                metric_type(name),
+                (
+                    format!("sysmetrics_zfs_arc_{}_total", name),
+                    MetricType::Counter
+                ),
+                "{}",
+                name
+            );
+        }
+        let counters = rows
+            .iter()
+            .filter(|r| metric_type(&r.name).1 == MetricType::Counter)
+            .count();
+        assert_eq!(counters, 70);
+    }
+
+    #[test]
+    fn test_zfs_collector_pools() {
+        let metrics = ZfsCollector
+            .collect_from_dir(Path::new(ZFS_FIXTURE_DIR))
+            .unwrap();
+
+        // Only tank has an io kstat.
+        let read = find_metric(&metrics, "sysmetrics_zfs_pool_io_read_bytes_total");
+        assert_eq!(
+            read.samples[0].labels,
+            vec![("pool".to_string(), "tank".to_string())]
+        );
+        assert_eq!(read.samples[0].value, 1073741824.0);
+        assert_eq!(
+            find_metric(&metrics, "sysmetrics_zfs_pool_io_written_bytes_total").samples[0].value,
+            2147483648.0
+        );
+        let rcnt = find_metric(&metrics, "sysmetrics_zfs_pool_io_rcnt");
+        assert_eq!(rcnt.metric_type, MetricType::Gauge);
+        assert_eq!(rcnt.samples[0].value, 2.0);
+        // Queue times are exported in seconds.
+        let wtime = find_metric(&metrics, "sysmetrics_zfs_pool_io_wtime_seconds_total");
+        assert_eq!(wtime.metric_type, MetricType::Counter);
+        assert!((wtime.samples[0].value - 0.005).abs() < 1e-12);
+        // The update timestamps are dropped.
+        assert!(!metrics.iter().any(|m| m.name.contains("update")));
+        assert_eq!(
+            metrics
+                .iter()
+                .filter(|m| m.name.starts_with("sysmetrics_zfs_pool_io_"))
+                .count(),
+            POOL_IO_COLUMNS.len()
+        );
+
+        let state = find_metric(&metrics, "sysmetrics_zfs_pool_state");
+        assert_eq!(state.samples.len(), 2 * POOL_STATES.len());
+        let active: Vec<(&str, &str)> = state
+            .samples
+            .iter()
+            .filter(|s| s.value == 1.0)
+            .map(|s| (s.labels[0].1.as_str(), s.labels[1].1.as_str()))
+            .collect();
+        assert_eq!(active, vec![("backup", "degraded"), ("tank", "online")]);
+    }
+
+    #[test]
+    fn test_zfs_collector_missing_root() {
+        assert_missing_root_not_supported(|root| ZfsCollector.collect_from_dir(root));
+    }
+}
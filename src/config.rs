@@ -66,27 +66,103 @@ fn default_log_format() -> String {
 pub struct CollectorsConfig {
     #[serde(default = "default_true")]
     pub cpu: bool,
+    /// Also export CPU time summed over all CPUs, without a `cpu` label.
+    #[serde(default)]
+    pub cpu_aggregate: bool,
     #[serde(default = "default_true")]
     pub memory: bool,
     #[serde(default = "default_true")]
     pub disk: bool,
     #[serde(default = "default_true")]
     pub network: bool,
+    #[serde(default = "default_true")]
+    pub filesystem: bool,
+    #[serde(default = "default_true")]
+    pub loadavg: bool,
+    #[serde(default = "default_true")]
+    pub pressure: bool,
+    #[serde(default = "default_true")]
+    pub vmstat: bool,
+    #[serde(default = "default_true")]
+    pub hwmon: bool,
+    #[serde(default = "default_true")]
+    pub thermal: bool,
+    #[serde(default = "default_true")]
+    pub cpufreq: bool,
+    #[serde(default = "default_true")]
+    pub process_states: bool,
+    #[serde(default = "default_true")]
+    pub cgroup: bool,
+    #[serde(default = "default_true")]
+    pub netstat: bool,
+    #[serde(default = "default_true")]
+    pub mdraid: bool,
+    #[serde(default = "default_true")]
+    pub zfs: bool,
+    #[serde(default = "default_true")]
+    pub btrfs: bool,
+    /// TCP socket states; off by default since the cost grows with the
+    /// number of connections.
+    #[serde(default)]
+    pub tcpstat: bool,
+    /// Per-process group metrics; off by default since it scans every PID.
+    #[serde(default)]
+    pub processes: bool,
+    #[serde(default)]
+    pub memory_config: MemoryConfig,
+    #[serde(default)]
+    pub process_states_config: ProcessStatesConfig,
     #[serde(default)]
     pub disk_config: DiskConfig,
     #[serde(default)]
     pub network_config: NetworkConfig,
+    #[serde(default)]
+    pub filesystem_config: FilesystemConfig,
+    #[serde(default)]
+    pub vmstat_config: VmstatConfig,
+    #[serde(default)]
+    pub processes_config: ProcessesConfig,
+    #[serde(default)]
+    pub cgroup_config: CgroupConfig,
+    #[serde(default)]
+    pub netstat_config: NetstatConfig,
+    #[serde(default)]
+    pub tcpstat_config: TcpstatConfig,
 }
 
 impl Default for CollectorsConfig {
     fn default() -> Self {
         Self {
             cpu: true,
+            cpu_aggregate: false,
             memory: true,
             disk: true,
             network: true,
+            filesystem: true,
+            loadavg: true,
+            pressure: true,
+            vmstat: true,
+            hwmon: true,
+            thermal: true,
+            cpufreq: true,
+            process_states: true,
+            cgroup: true,
+            netstat: true,
+            mdraid: true,
+            zfs: true,
+            btrfs: true,
+            tcpstat: false,
+            processes: false,
+            memory_config: MemoryConfig::default(),
+            process_states_config: ProcessStatesConfig::default(),
             disk_config: DiskConfig::default(),
             network_config: NetworkConfig::default(),
+            filesystem_config: FilesystemConfig::default(),
+            vmstat_config: VmstatConfig::default(),
+            processes_config: ProcessesConfig::default(),
+            cgroup_config: CgroupConfig::default(),
+            netstat_config: NetstatConfig::default(),
+            tcpstat_config: TcpstatConfig::default(),
         }
     }
 }
@@ -95,22 +171,62 @@ fn default_true() -> bool {
     true
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct MemoryConfig {
+    /// Export every /proc/meminfo field, not just the fixed set.
+    #[serde(default)]
+    pub all_fields: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ProcessStatesConfig {
+    /// Also export the state of every thread from
+    /// /proc/[pid]/task/[tid]/stat; off by default since it reads one file
+    /// per thread.
+    #[serde(default)]
+    pub thread_states: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DiskConfig {
     #[serde(default = "default_disk_exclude")]
     pub exclude_pattern: String,
+    /// Export `sysmetrics_disk_parent_info`, mapping each device to the
+    /// devices it is built on (e.g. an LVM volume to its physical volumes)
+    #[serde(default)]
+    pub parent_info: bool,
+    /// Add the `model`, `serial`, `wwid` and `id` labels of
+    /// `sysmetrics_disk_info` to every disk I/O series
+    #[serde(default)]
+    pub identity_labels: bool,
+    /// Export iostat-style rates (IOPS, await, utilization, queue size)
+    /// computed from the previous scrape
+    #[serde(default)]
+    pub derived_stats: bool,
+    /// Minimum time between the snapshots rates are computed from; closer
+    /// scrapes repeat the last rates
+    #[serde(default = "default_derived_min_interval_ms")]
+    pub derived_min_interval_ms: u64,
 }
 
 impl Default for DiskConfig {
     fn default() -> Self {
         Self {
             exclude_pattern: default_disk_exclude(),
+            parent_info: false,
+            identity_labels: false,
+            derived_stats: false,
+            derived_min_interval_ms: default_derived_min_interval_ms(),
         }
     }
 }
 
+fn default_derived_min_interval_ms() -> u64 {
+    1000
+}
+
 fn default_disk_exclude() -> String {
-    "^(loop|ram|dm-)".to_string()
+    "^(loop|ram)".to_string()
 }
 
 #[derive(Debug, Deserialize)]
@@ -131,6 +247,143 @@ fn default_network_exclude() -> String {
     "^(lo|veth)".to_string()
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FilesystemConfig {
+    #[serde(default)]
+    pub mountpoint_include_pattern: String,
+    #[serde(default = "default_mountpoint_exclude")]
+    pub mountpoint_exclude_pattern: String,
+    #[serde(default)]
+    pub fstype_include_pattern: String,
+    #[serde(default = "default_fstype_exclude")]
+    pub fstype_exclude_pattern: String,
+    /// Maximum time to wait for statvfs on a single mount.
+    #[serde(default = "default_mount_timeout_ms")]
+    pub mount_timeout_ms: u64,
+}
+
+impl Default for FilesystemConfig {
+    fn default() -> Self {
+        Self {
+            mountpoint_include_pattern: String::new(),
+            mountpoint_exclude_pattern: default_mountpoint_exclude(),
+            fstype_include_pattern: String::new(),
+            fstype_exclude_pattern: default_fstype_exclude(),
+            mount_timeout_ms: default_mount_timeout_ms(),
+        }
+    }
+}
+
+fn default_mountpoint_exclude() -> String {
+    "^/(dev|proc|run/credentials/.+|sys|var/lib/docker/.+|var/lib/containers/storage/.+)($|/)"
+        .to_string()
+}
+
+fn default_fstype_exclude() -> String {
+    "^(autofs|binfmt_misc|bpf|cgroup2?|configfs|debugfs|devpts|devtmpfs|fusectl|hugetlbfs|iso9660|mqueue|nsfs|overlay|proc|procfs|pstore|rpc_pipefs|securityfs|selinuxfs|squashfs|sysfs|tracefs)$"
+        .to_string()
+}
+
+fn default_mount_timeout_ms() -> u64 {
+    5000
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VmstatConfig {
+    /// Regex pattern — only matching /proc/vmstat fields are exported
+    #[serde(default = "default_vmstat_include")]
+    pub include_pattern: String,
+}
+
+impl Default for VmstatConfig {
+    fn default() -> Self {
+        Self {
+            include_pattern: default_vmstat_include(),
+        }
+    }
+}
+
+fn default_vmstat_include() -> String {
+    "^(pgfault|pgmajfault|pswp|oom_kill|pgpg)".to_string()
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ProcessesConfig {
+    /// Groups are matched in order; a process is counted in the first group
+    /// it matches and ignored if it matches none.
+    #[serde(default)]
+    pub groups: Vec<ProcessGroupConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProcessGroupConfig {
+    pub name: String,
+    /// Regex pattern matched against the process name (`comm`)
+    #[serde(default)]
+    pub comm_pattern: Option<String>,
+    /// Regex pattern matched against the full command line
+    #[serde(default)]
+    pub cmdline_pattern: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CgroupConfig {
+    /// How many levels below /sys/fs/cgroup to walk; 0 reports only the root
+    #[serde(default = "default_cgroup_max_depth")]
+    pub max_depth: usize,
+    #[serde(default)]
+    pub include_pattern: String,
+    #[serde(default = "default_cgroup_exclude")]
+    pub exclude_pattern: String,
+}
+
+impl Default for CgroupConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: default_cgroup_max_depth(),
+            include_pattern: String::new(),
+            exclude_pattern: default_cgroup_exclude(),
+        }
+    }
+}
+
+fn default_cgroup_max_depth() -> usize {
+    2
+}
+
+fn default_cgroup_exclude() -> String {
+    r"\.(mount|socket|swap)$".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NetstatConfig {
+    /// Regex pattern matched against `Protocol_Field` (e.g. `Tcp_RetransSegs`)
+    /// of /proc/net/snmp, netstat and snmp6 statistics and of sockstat
+    /// socket usage (e.g. `TCP_inuse`); only matching fields are exported
+    #[serde(default = "default_netstat_field_include")]
+    pub field_include_pattern: String,
+}
+
+impl Default for NetstatConfig {
+    fn default() -> Self {
+        Self {
+            field_include_pattern: default_netstat_field_include(),
+        }
+    }
+}
+
+fn default_netstat_field_include() -> String {
+    "^(.*_(InErrors|InErrs)|Ip_Forwarding|Ip(6|Ext)_(InOctets|OutOctets)|Icmp6?_(InMsgs|OutMsgs)|TcpExt_(Listen.*|Syncookies.*|TCPSynRetrans|TCPTimeouts|TCPOFOQueue)|Tcp_(ActiveOpens|InSegs|OutSegs|OutRsts|PassiveOpens|RetransSegs|CurrEstab)|Udp6?_(InDatagrams|OutDatagrams|NoPorts|RcvbufErrors|SndbufErrors)|sockets_used|(TCP|UDP)6?_inuse|TCP_(orphan|tw|alloc|mem)|UDP_mem|FRAG6?_memory)$"
+        .to_string()
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TcpstatConfig {
+    /// Local ports that get a per-port breakdown of connection states
+    #[serde(default)]
+    pub ports: Vec<u16>,
+}
+
 impl Config {
     /// Load configuration from file (if it exists) and apply CLI overrides.
     pub fn load(cli: &Cli) -> anyhow::Result<Self> {
@@ -156,6 +409,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use regex::Regex;
 
     #[test]
     fn test_default_config() {
@@ -163,17 +417,55 @@ mod tests {
         assert_eq!(config.server.listen, "0.0.0.0:9101");
         assert_eq!(config.server.metrics_path, "/metrics");
         assert!(config.collectors.cpu);
+        assert!(!config.collectors.cpu_aggregate);
         assert!(config.collectors.memory);
         assert!(config.collectors.disk);
         assert!(config.collectors.network);
-        assert_eq!(
-            config.collectors.disk_config.exclude_pattern,
-            "^(loop|ram|dm-)"
+        assert!(config.collectors.filesystem);
+        assert!(config.collectors.loadavg);
+        assert!(config.collectors.pressure);
+        assert!(config.collectors.vmstat);
+        assert!(config.collectors.hwmon);
+        assert!(config.collectors.thermal);
+        assert!(config.collectors.cpufreq);
+        assert!(config.collectors.process_states);
+        assert!(config.collectors.cgroup);
+        assert!(config.collectors.netstat);
+        assert!(config.collectors.mdraid);
+        assert!(config.collectors.zfs);
+        assert!(config.collectors.btrfs);
+        assert!(!config.collectors.tcpstat);
+        assert!(config.collectors.tcpstat_config.ports.is_empty());
+        assert!(!config.collectors.processes);
+        assert!(config.collectors.processes_config.groups.is_empty());
+        assert_eq!(config.collectors.cgroup_config.max_depth, 2);
+        assert_eq!(config.collectors.cgroup_config.include_pattern, "");
+        assert!(
+            Regex::new(&config.collectors.netstat_config.field_include_pattern)
+                .unwrap()
+                .is_match("Tcp_RetransSegs")
         );
+        assert!(
+            Regex::new(&config.collectors.netstat_config.field_include_pattern)
+                .unwrap()
+                .is_match("TCP_inuse")
+        );
+        assert!(!config.collectors.memory_config.all_fields);
+        assert!(!config.collectors.process_states_config.thread_states);
+        assert_eq!(config.collectors.disk_config.exclude_pattern, "^(loop|ram)");
+        assert!(!config.collectors.disk_config.parent_info);
+        assert!(!config.collectors.disk_config.identity_labels);
+        assert!(!config.collectors.disk_config.derived_stats);
+        assert_eq!(config.collectors.disk_config.derived_min_interval_ms, 1000);
         assert_eq!(
             config.collectors.network_config.exclude_pattern,
             "^(lo|veth)"
         );
+        assert_eq!(config.collectors.filesystem_config.mount_timeout_ms, 5000);
+        assert_eq!(
+            config.collectors.vmstat_config.include_pattern,
+            "^(pgfault|pgmajfault|pswp|oom_kill|pgpg)"
+        );
     }
 
     #[test]
@@ -188,6 +480,10 @@ cpu = true
 memory = false
 disk = true
 network = false
+tcpstat = true
+
+[collectors.tcpstat_config]
+ports = [22, 443]
 "#;
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.server.listen, "127.0.0.1:9102");
@@ -196,5 +492,48 @@ network = false
         assert!(!config.collectors.memory);
         assert!(config.collectors.disk);
         assert!(!config.collectors.network);
+        assert!(config.collectors.tcpstat);
+        assert_eq!(config.collectors.tcpstat_config.ports, vec![22, 443]);
+    }
+
+    #[test]
+    fn test_parse_filesystem_config() {
+        let toml_str = r#"
+[collectors.filesystem_config]
+fstype_include_pattern = "^(ext4|xfs)$"
+mount_timeout_ms = 1000
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let fs_config = &config.collectors.filesystem_config;
+        assert_eq!(fs_config.fstype_include_pattern, "^(ext4|xfs)$");
+        assert_eq!(fs_config.mount_timeout_ms, 1000);
+        assert_eq!(
+            fs_config.mountpoint_exclude_pattern,
+            default_mountpoint_exclude()
+        );
+    }
+
+    #[test]
+    fn test_parse_processes_config() {
+        let toml_str = r#"
+[collectors]
+processes = true
+
+[[collectors.processes_config.groups]]
+name = "postgres"
+comm_pattern = "^postgres$"
+
+[[collectors.processes_config.groups]]
+name = "nginx"
+cmdline_pattern = "^nginx: "
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.collectors.processes);
+        let groups = &config.collectors.processes_config.groups;
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "postgres");
+        assert_eq!(groups[0].comm_pattern.as_deref(), Some("^postgres$"));
+        assert_eq!(groups[0].cmdline_pattern, None);
+        assert_eq!(groups[1].cmdline_pattern.as_deref(), Some("^nginx: "));
     }
 }
@@ -12,4 +12,9 @@ pub enum CollectorError {
         field: String,
         raw: String,
     },
+
+    /// The kernel does not provide the data source (e.g. PSI disabled or
+    /// not compiled in). Not treated as a scrape failure.
+    #[error("{path} is not supported on this system")]
+    NotSupported { path: String },
 }
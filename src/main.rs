@@ -4,14 +4,30 @@ mod error;
 mod server;
 
 use clap::Parser;
+use collector::btrfs::BtrfsCollector;
+use collector::cgroup::CgroupCollector;
 use collector::cpu::CpuCollector;
+use collector::cpufreq::CpuFreqCollector;
 use collector::disk::DiskCollector;
+use collector::filesystem::FilesystemCollector;
+use collector::hwmon::HwmonCollector;
+use collector::loadavg::LoadAvgCollector;
+use collector::mdraid::MdraidCollector;
 use collector::memory::MemoryCollector;
+use collector::netstat::NetstatCollector;
 use collector::network::NetworkCollector;
+use collector::pressure::PressureCollector;
+use collector::process_states::ProcessStatesCollector;
+use collector::processes::{ProcessGroupRule, ProcessesCollector};
+use collector::tcpstat::TcpstatCollector;
+use collector::thermal::ThermalCollector;
+use collector::vmstat::VmstatCollector;
+use collector::zfs::ZfsCollector;
 use collector::Registry;
 use config::{Cli, Config};
 use server::{build_router, AppState};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -40,19 +56,106 @@ async fn main() -> anyhow::Result<()> {
     let mut registry = Registry::new();
 
     if config.collectors.cpu {
-        registry.register(Box::new(CpuCollector));
+        registry.register(Box::new(CpuCollector::new(config.collectors.cpu_aggregate)));
     }
     if config.collectors.memory {
-        registry.register(Box::new(MemoryCollector));
+        let collector = MemoryCollector::new(config.collectors.memory_config.all_fields);
+        registry.register(Box::new(collector));
     }
     if config.collectors.disk {
-        let collector = DiskCollector::new(&config.collectors.disk_config.exclude_pattern)?;
+        let disk_config = &config.collectors.disk_config;
+        let collector = DiskCollector::new(
+            &disk_config.exclude_pattern,
+            disk_config.parent_info,
+            disk_config.identity_labels,
+            disk_config
+                .derived_stats
+                .then(|| Duration::from_millis(disk_config.derived_min_interval_ms)),
+        )?;
         registry.register(Box::new(collector));
     }
     if config.collectors.network {
         let collector = NetworkCollector::new(&config.collectors.network_config.exclude_pattern)?;
         registry.register(Box::new(collector));
     }
+    if config.collectors.filesystem {
+        let fs_config = &config.collectors.filesystem_config;
+        let collector = FilesystemCollector::new(
+            &fs_config.mountpoint_include_pattern,
+            &fs_config.mountpoint_exclude_pattern,
+            &fs_config.fstype_include_pattern,
+            &fs_config.fstype_exclude_pattern,
+            Duration::from_millis(fs_config.mount_timeout_ms),
+        )?;
+        registry.register(Box::new(collector));
+    }
+    if config.collectors.loadavg {
+        registry.register(Box::new(LoadAvgCollector));
+    }
+    if config.collectors.pressure {
+        registry.register(Box::new(PressureCollector));
+    }
+    if config.collectors.vmstat {
+        let collector = VmstatCollector::new(&config.collectors.vmstat_config.include_pattern)?;
+        registry.register(Box::new(collector));
+    }
+    if config.collectors.hwmon {
+        registry.register(Box::new(HwmonCollector));
+    }
+    if config.collectors.thermal {
+        registry.register(Box::new(ThermalCollector));
+    }
+    if config.collectors.cpufreq {
+        registry.register(Box::new(CpuFreqCollector));
+    }
+    if config.collectors.process_states {
+        registry.register(Box::new(ProcessStatesCollector::new(
+            config.collectors.process_states_config.thread_states,
+        )));
+    }
+    if config.collectors.cgroup {
+        let cgroup_config = &config.collectors.cgroup_config;
+        let collector = CgroupCollector::new(
+            &cgroup_config.include_pattern,
+            &cgroup_config.exclude_pattern,
+            cgroup_config.max_depth,
+        )?;
+        registry.register(Box::new(collector));
+    }
+    if config.collectors.netstat {
+        let collector =
+            NetstatCollector::new(&config.collectors.netstat_config.field_include_pattern)?;
+        registry.register(Box::new(collector));
+    }
+    if config.collectors.mdraid {
+        registry.register(Box::new(MdraidCollector));
+    }
+    if config.collectors.zfs {
+        registry.register(Box::new(ZfsCollector));
+    }
+    if config.collectors.btrfs {
+        registry.register(Box::new(BtrfsCollector));
+    }
+    if config.collectors.tcpstat {
+        let ports = config.collectors.tcpstat_config.ports.clone();
+        registry.register(Box::new(TcpstatCollector::new(ports)));
+    }
+    if config.collectors.processes {
+        let rules = config
+            .collectors
+            .processes_config
+            .groups
+            .iter()
+            .map(|g| {
+                ProcessGroupRule::new(
+                    &g.name,
+                    g.comm_pattern.as_deref(),
+                    g.cmdline_pattern.as_deref(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        registry.register(Box::new(ProcessesCollector::new(rules)));
+    }
 
     let state = Arc::new(AppState {
         registry,
@@ -1,5 +1,8 @@
+use crate::collector::self_process::{read_self_process, self_process_metrics};
 use crate::collector::{render_metrics, Metric, MetricSample, MetricType, Registry};
+use crate::error::CollectorError;
 use axum::{extract::State, http::StatusCode, response::Html, routing::get, Router};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -46,6 +49,7 @@ async fn metrics_handler(
     let mut duration_samples = Vec::new();
     let mut success_samples = Vec::new();
     let mut error_samples = Vec::new();
+    let mut supported_samples = Vec::new();
 
     for result in &results {
         let collector_name = result.name;
@@ -56,6 +60,12 @@ async fn metrics_handler(
             value: duration_secs,
         });
 
+        let supported = !matches!(result.result, Err(CollectorError::NotSupported { .. }));
+        supported_samples.push(MetricSample {
+            labels: vec![("collector".to_string(), collector_name.to_string())],
+            value: if supported { 1.0 } else { 0.0 },
+        });
+
         match &result.result {
             Ok(metrics) => {
                 success_samples.push(MetricSample {
@@ -68,6 +78,19 @@ async fn metrics_handler(
                 });
                 all_metrics.extend(metrics.clone());
             }
+            Err(e @ CollectorError::NotSupported { .. }) => {
+                // A missing kernel feature is a property of the host, not a
+                // scrape failure.
+                tracing::debug!(collector = collector_name, error = %e, "collector not supported");
+                success_samples.push(MetricSample {
+                    labels: vec![("collector".to_string(), collector_name.to_string())],
+                    value: 1.0,
+                });
+                error_samples.push(MetricSample {
+                    labels: vec![("collector".to_string(), collector_name.to_string())],
+                    value: 0.0,
+                });
+            }
             Err(e) => {
                 tracing::error!(collector = collector_name, error = %e, "collector failed");
                 success_samples.push(MetricSample {
@@ -114,6 +137,14 @@ async fn metrics_handler(
         samples: error_samples,
     });
 
+    meta_metrics.push(Metric {
+        name: "sysmetrics_collector_supported".to_string(),
+        help: "Whether the collector's data source is available on this system (1) or not (0)."
+            .to_string(),
+        metric_type: MetricType::Gauge,
+        samples: supported_samples,
+    });
+
     meta_metrics.push(Metric {
         name: "sysmetrics_build_info".to_string(),
         help: "Build information for sysmetrics-rs.".to_string(),
@@ -127,6 +158,12 @@ async fn metrics_handler(
         }],
     });
 
+    // Resource usage of the exporter itself
+    match read_self_process(Path::new("/proc")) {
+        Ok(stats) => meta_metrics.extend(self_process_metrics(&stats)),
+        Err(e) => tracing::warn!(error = %e, "failed to read exporter process stats"),
+    }
+
     all_metrics.extend(meta_metrics);
 
     let body = render_metrics(&all_metrics);
@@ -141,7 +178,7 @@ async fn metrics_handler(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::collector::Registry;
+    use crate::collector::{Collector, Registry};
     use axum::body::Body;
     use axum::http::Request;
     use tower::ServiceExt;
@@ -154,6 +191,20 @@ mod tests {
         })
     }
 
+    struct UnsupportedCollector;
+
+    impl Collector for UnsupportedCollector {
+        fn name(&self) -> &'static str {
+            "unsupported"
+        }
+
+        fn collect(&self) -> Result<Vec<Metric>, CollectorError> {
+            Err(CollectorError::NotSupported {
+                path: "/proc/pressure/cpu".to_string(),
+            })
+        }
+    }
+
     #[tokio::test]
     async fn test_health_endpoint() {
         let app = build_router(test_state());
@@ -213,5 +264,35 @@ mod tests {
         // Should contain meta-metrics even with no collectors
         assert!(body_str.contains("sysmetrics_build_info"));
         assert!(body_str.contains("sysmetrics_scrape_duration_seconds_total"));
+        assert!(body_str.contains("# TYPE process_cpu_seconds_total counter"));
+        assert!(body_str.contains("process_resident_memory_bytes "));
+        assert!(body_str.contains("process_start_time_seconds "));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_not_supported_collector() {
+        let mut registry = Registry::new();
+        registry.register(Box::new(UnsupportedCollector));
+        let state = Arc::new(AppState {
+            registry,
+            version: "0.1.0-test",
+            rustc_version: "test",
+        });
+        let app = build_router(state);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body_str = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body_str.contains("sysmetrics_collector_success{collector=\"unsupported\"} 1"));
+        assert!(body_str.contains("sysmetrics_collector_supported{collector=\"unsupported\"} 0"));
     }
 }